@@ -40,7 +40,8 @@ async fn main() -> eyre::Result<()> {
         )
         .signer(LocalSigner::rand())
         .build();
-    let listener_handle = client.listener(Duration::from_secs(2)).await;
+    // Receipts let the filter skip failed transfers
+    let listener_handle = client.receipt_listener(Duration::from_secs(2)).await;
 
     // Set up an in-memory token registry with USDT contract
     let registry = InMemoryTokenRegistry::from(
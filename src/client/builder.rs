@@ -1,31 +1,65 @@
+use std::collections::HashMap;
+
+use alloy_primitives::B256;
+use alloy_sol_types::SolEvent;
 use derivative::Derivative;
 use eyre::eyre;
+use futures::{Stream, StreamExt, TryStreamExt};
 use time::OffsetDateTime;
 
 use crate::Result;
+use crate::contracts::AbiEncode;
+use crate::domain::Hash32;
 use crate::domain::Message;
 use crate::domain::account::Account;
 use crate::domain::address::TronAddress;
+use crate::domain::block::BlockExtention;
 use crate::domain::contract::Abi;
 use crate::domain::contract::AccountPermissionUpdateContract;
+use crate::domain::contract::AssetIssueContract;
 use crate::domain::contract::CancelAllUnfreezeV2Contract;
 use crate::domain::contract::Contract;
 use crate::domain::contract::ContractArtifact;
 use crate::domain::contract::CreateSmartContract;
 use crate::domain::contract::DelegateResourceContract;
+use crate::domain::contract::ExchangeCreateContract;
+use crate::domain::contract::ExchangeInjectContract;
+use crate::domain::contract::ExchangeTransactionContract;
+use crate::domain::contract::ExchangeWithdrawContract;
 use crate::domain::contract::FreezeBalanceV2Contract;
+use crate::domain::contract::FrozenSupply;
+use crate::domain::contract::MarketCancelOrderContract;
+use crate::domain::contract::MarketSellAssetContract;
+use crate::domain::contract::ParticipateAssetIssueContract;
+use crate::domain::contract::ProposalApproveContract;
+use crate::domain::contract::ProposalCreateContract;
+use crate::domain::contract::ProposalDeleteContract;
 use crate::domain::contract::ResourceCode;
 use crate::domain::contract::SmartContract;
+use crate::domain::contract::TransferAssetContract;
 use crate::domain::contract::TransferContract;
 use crate::domain::contract::TriggerSmartContract;
 use crate::domain::contract::UnDelegateResourceContract;
 use crate::domain::contract::UnfreezeBalanceV2Contract;
+use crate::domain::contract::UpdateBrokerageContract;
+use crate::domain::contract::Vote;
+use crate::domain::contract::VoteWitnessContract;
+use crate::domain::contract::WithdrawBalanceContract;
 use crate::domain::contract::WithdrawExpireUnfreezeContract;
+use crate::domain::contract::WitnessCreateContract;
+use crate::domain::contract::WitnessUpdateContract;
+use crate::domain::exchange::TRX_TOKEN_ID;
+use crate::domain::market::MarketOrderState;
 use crate::domain::permission::Permission;
 use crate::domain::permission::PermissionParams;
-use crate::domain::transaction::Transaction;
+use crate::domain::proposal::{ChainParameter, ProposalState};
+use crate::domain::transaction::{
+    Log, LogEntry, LogPage, Transaction, TransactionInfo,
+};
 use crate::domain::trx::Trx;
 use crate::error::Error;
+use crate::listener::block_cache::{BlockCache, InMemoryBlockCache};
+use crate::listener::{Listener, ListenerError, ListenerEvent, Reorg};
 use crate::signer::PrehashSigner;
 use crate::trx;
 
@@ -97,7 +131,10 @@ where
             })
             .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
 
-        let latest_block = transfer.client.provider.get_now_block().await?;
+        let (latest_block, fee_parameters) = tokio::try_join!(
+            transfer.client.provider.get_now_block(),
+            transfer.client.fee_parameters()
+        )?;
         let transaction = Transaction::new(
             Contract {
                 contract_type:
@@ -115,7 +152,7 @@ where
         );
         let activation_checks = vec![ActivationFeeCheck {
             address: transfer.to,
-            fee: trx!(1.0 TRX),
+            fee: fee_parameters.activation_fee(),
         }];
         PendingTransaction::new(
             transfer.client,
@@ -129,6 +166,317 @@ where
     }
 }
 
+/// Max asset precision
+const MAX_ASSET_PRECISION: i32 = 6;
+
+fn check_asset_issue(
+    asset: &AssetIssueContract,
+    now: OffsetDateTime,
+) -> Result<()> {
+    if asset.name.is_empty() || asset.url.is_empty() {
+        return Err(Error::InvalidInput(
+            "asset name and url must not be empty".into(),
+        ));
+    }
+    if asset.total_supply <= 0 || asset.trx_num <= Trx::ZERO || asset.num <= 0 {
+        return Err(Error::InvalidInput(
+            "total supply and exchange rate must be positive".into(),
+        ));
+    }
+    if !(0..=MAX_ASSET_PRECISION).contains(&asset.precision) {
+        return Err(Error::InvalidInput(format!(
+            "precision must be in range 0..={MAX_ASSET_PRECISION}"
+        )));
+    }
+    if asset.start_time <= now || asset.end_time <= asset.start_time {
+        return Err(Error::InvalidInput(
+            "participation must start in the future and end after start".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_participation(
+    asset: &AssetIssueContract,
+    owner: TronAddress,
+    now: OffsetDateTime,
+) -> Result<()> {
+    if asset.owner_address == owner {
+        return Err(Error::InvalidInput(
+            "issuer cannot participate in its own asset".into(),
+        ));
+    }
+    if now < asset.start_time || now >= asset.end_time {
+        return Err(Error::PreconditionFailed(format!(
+            "asset {} participation is not open",
+            asset.id
+        )));
+    }
+    Ok(())
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct TransferAsset<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) to: TronAddress,
+    /// TRC-10 asset id, e.g. `"1002000"`
+    #[builder(into)]
+    pub(super) asset_id: String,
+    /// Amount in the smallest asset units
+    pub(super) amount: i64,
+    pub(super) owner: Option<TronAddress>,
+    pub(super) memo: Option<Message>,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: transfer_asset_builder::IsComplete>
+    TransferAssetBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let transfer = self.build_internal();
+        let owner = transfer
+            .owner
+            .or_else(|| {
+                transfer.client.signer.as_ref().and_then(|s| s.address())
+            })
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+        if transfer.amount <= 0 {
+            return Err(Error::InvalidInput("amount must be positive".into()));
+        }
+        if transfer.to == owner {
+            return Err(Error::InvalidInput(
+                "cannot transfer asset to itself".into(),
+            ));
+        }
+        if transfer.asset_id == TRX_TOKEN_ID {
+            return Err(Error::InvalidInput(
+                "TRX is not an asset, use transfer()".into(),
+            ));
+        }
+
+        let account = transfer.client.provider.get_account(owner).await?;
+        check_token_balance(&account, &transfer.asset_id, transfer.amount)?;
+
+        let (latest_block, fee_parameters) = tokio::try_join!(
+            transfer.client.provider.get_now_block(),
+            transfer.client.fee_parameters()
+        )?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::TransferAssetContract(
+                        TransferAssetContract {
+                            asset_name: transfer.asset_id.as_str().into(),
+                            owner_address: owner,
+                            to_address: transfer.to,
+                            amount: Trx::from_sun(transfer.amount),
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            transfer.memo.unwrap_or_default(),
+        );
+        let activation_checks = vec![ActivationFeeCheck {
+            address: transfer.to,
+            fee: fee_parameters.activation_fee(),
+        }];
+        PendingTransaction::new(
+            transfer.client,
+            transaction,
+            owner,
+            Trx::ZERO,
+            activation_checks,
+            transfer.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct AssetIssue<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    #[builder(into)]
+    pub(super) name: Message,
+    #[builder(into)]
+    pub(super) abbr: Message,
+    /// Total supply in the smallest asset units
+    pub(super) total_supply: i64,
+    /// Decimals, 0..=6
+    #[builder(default = 0)]
+    pub(super) precision: i32,
+    /// Participants pay `trx_num` for `num` asset units
+    pub(super) trx_num: Trx,
+    pub(super) num: i32,
+    /// Participation window
+    pub(super) start_time: OffsetDateTime,
+    pub(super) end_time: OffsetDateTime,
+    #[builder(into)]
+    pub(super) url: Message,
+    pub(super) description: Option<Message>,
+    /// Bandwidth the issuer grants each holder for transfers
+    #[builder(default = 0)]
+    pub(super) free_asset_net_limit: i64,
+    /// Bandwidth the issuer grants all holders together
+    #[builder(default = 0)]
+    pub(super) public_free_asset_net_limit: i64,
+    #[builder(default)]
+    pub(super) frozen_supply: Vec<FrozenSupply>,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: asset_issue_builder::IsComplete>
+    AssetIssueBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    /// Issuing burns `getAssetIssueFee` TRX, an account can issue one asset
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let issue = self.build_internal();
+        let owner = issue
+            .owner
+            .or_else(|| issue.client.signer.as_ref().and_then(|s| s.address()))
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+        let contract = AssetIssueContract {
+            owner_address: owner,
+            name: issue.name,
+            abbr: issue.abbr,
+            total_supply: issue.total_supply,
+            frozen_supply: issue.frozen_supply,
+            trx_num: issue.trx_num,
+            precision: issue.precision,
+            num: issue.num,
+            start_time: issue.start_time,
+            end_time: issue.end_time,
+            description: issue.description.unwrap_or_default(),
+            url: issue.url,
+            free_asset_net_limit: issue.free_asset_net_limit,
+            public_free_asset_net_limit: issue.public_free_asset_net_limit,
+            ..Default::default()
+        };
+        check_asset_issue(&contract, OffsetDateTime::now_utc())?;
+
+        let (account, chain_parameters) = tokio::try_join!(
+            issue.client.provider.get_account(owner),
+            issue.client.provider.chain_parameters()
+        )?;
+        if !account.asset_issued_id.is_empty() {
+            return Err(Error::PreconditionFailed(format!(
+                "{owner} has already issued an asset"
+            )));
+        }
+
+        let latest_block = issue.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::AssetIssueContract(
+                        contract,
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default(),
+        );
+        PendingTransaction::new(
+            issue.client,
+            transaction,
+            owner,
+            chain_parameters.asset_issue_fee.unwrap_or_default(),
+            Vec::new(),
+            issue.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct ParticipateAsset<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    /// TRC-10 asset id, e.g. `"1002000"`
+    #[builder(into)]
+    pub(super) asset_id: String,
+    /// TRX paid to the issuer
+    pub(super) amount: Trx,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: participate_asset_builder::IsComplete>
+    ParticipateAssetBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    /// Buys asset from its issuer at the issue exchange rate
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let participate = self.build_internal();
+        let owner = participate
+            .owner
+            .or_else(|| {
+                participate.client.signer.as_ref().and_then(|s| s.address())
+            })
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+        if participate.amount <= Trx::ZERO {
+            return Err(Error::InvalidInput("amount must be positive".into()));
+        }
+
+        let asset = participate
+            .client
+            .provider
+            .get_asset_issue_by_id(&participate.asset_id)
+            .await?;
+        check_participation(&asset, owner, OffsetDateTime::now_utc())?;
+
+        let latest_block = participate.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::ParticipateAssetIssueContract(
+                        ParticipateAssetIssueContract {
+                            owner_address: owner,
+                            to_address: asset.owner_address,
+                            asset_name: participate.asset_id.as_str().into(),
+                            amount: participate.amount,
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default()
+        );
+        PendingTransaction::new(
+            participate.client,
+            transaction,
+            owner,
+            participate.amount,
+            Vec::new(),
+            participate.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct PermissionHandler<'a, P, S> {
@@ -261,6 +609,7 @@ where
 #[derive(bon::Builder)]
 #[builder(start_fn = with_client)]
 #[builder(finish_fn(vis = "", name = build_internal))]
+#[builder(state_mod(vis = "pub"))]
 pub struct FreezeBalance<'a, P, S> {
     #[builder(start_fn)]
     pub(super) client: &'a Client<P, S>,
@@ -446,6 +795,7 @@ where
 #[derive(bon::Builder)]
 #[builder(start_fn = with_client)]
 #[builder(finish_fn(vis = "", name = build_internal))]
+#[builder(state_mod(vis = "pub"))]
 pub struct Delegate<'a, P, S> {
     #[builder(start_fn)]
     pub(super) client: &'a Client<P, S>,
@@ -818,3 +1168,1805 @@ where
         Ok(ret)
     }
 }
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct VoteWitness<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    /// Replaces all previous votes of the owner
+    pub(super) votes: Vec<Vote>,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: vote_witness_builder::IsComplete>
+    VoteWitnessBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        /// Max witnesses voted for in one transaction
+        const MAX_VOTES: usize = 30;
+
+        let vote = self.build_internal();
+        let owner = vote
+            .owner
+            .or_else(|| vote.client.signer.as_ref().and_then(|s| s.address()))
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+
+        if vote.votes.is_empty() || vote.votes.len() > MAX_VOTES {
+            return Err(Error::InvalidInput(format!(
+                "votes count must be in range 1..={MAX_VOTES}"
+            )));
+        }
+        if let Some(v) = vote.votes.iter().find(|v| v.vote_count <= 0) {
+            return Err(Error::InvalidInput(format!(
+                "vote count for {} must be positive",
+                v.vote_address
+            )));
+        }
+
+        let account = vote.client.provider.get_account(owner).await?;
+        let tron_power = account.tron_power();
+        let total: i64 = vote.votes.iter().map(|v| v.vote_count).sum();
+        if total > tron_power {
+            return Err(Error::PreconditionFailed(format!(
+                "not enough tron power: {tron_power}, but votes: {total}"
+            )));
+        }
+        for v in &vote.votes {
+            let witness =
+                vote.client.provider.get_account(v.vote_address).await?;
+            if !witness.is_witness {
+                return Err(Error::InvalidInput(format!(
+                    "{} is not a witness",
+                    v.vote_address
+                )));
+            }
+        }
+
+        let latest_block = vote.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::VoteWitnessContract(
+                        VoteWitnessContract {
+                            owner_address: owner,
+                            votes: vote.votes,
+                            support: true,
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default(),
+        );
+        PendingTransaction::new(
+            vote.client,
+            transaction,
+            owner,
+            Trx::ZERO,
+            Vec::new(),
+            vote.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct WithdrawRewards<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: withdraw_rewards_builder::IsComplete>
+    WithdrawRewardsBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        /// Rewards can be withdrawn once per day
+        const COOLDOWN: time::Duration = time::Duration::days(1);
+
+        let withdraw = self.build_internal();
+        let owner = withdraw
+            .owner
+            .or_else(|| {
+                withdraw.client.signer.as_ref().and_then(|s| s.address())
+            })
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+
+        let (account, reward) = tokio::try_join!(
+            withdraw.client.provider.get_account(owner),
+            withdraw.client.provider.get_reward(owner)
+        )?;
+        let next_withdraw = account.latest_withdraw_time + COOLDOWN;
+        if next_withdraw > OffsetDateTime::now_utc() {
+            return Err(Error::PreconditionFailed(format!(
+                "rewards can be withdrawn once per day, next at {next_withdraw}"
+            )));
+        }
+        // Witness allowance is withdrawn together with voting rewards
+        if reward + Trx::from_sun(account.allowance) == Trx::ZERO {
+            return Err(Error::PreconditionFailed(
+                "no rewards to withdraw".into(),
+            ));
+        }
+
+        let latest_block = withdraw.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::WithdrawBalanceContract(
+                        WithdrawBalanceContract {
+                            owner_address: owner,
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default()
+        );
+        PendingTransaction::new(
+            withdraw.client,
+            transaction,
+            owner,
+            Trx::ZERO,
+            Vec::new(),
+            withdraw.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+/// Max witness url length in bytes
+const MAX_WITNESS_URL_LEN: usize = 256;
+
+fn check_witness_url(url: &Message) -> Result<()> {
+    if url.is_empty() || url.len() > MAX_WITNESS_URL_LEN {
+        return Err(Error::InvalidInput(format!(
+            "witness url length must be in range 1..={MAX_WITNESS_URL_LEN}"
+        )));
+    }
+    Ok(())
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct WitnessCreate<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    /// Witness website
+    #[builder(into)]
+    pub(super) url: Message,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: witness_create_builder::IsComplete>
+    WitnessCreateBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    /// Creating a witness burns `getAccountUpgradeCost` TRX
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let create = self.build_internal();
+        let owner = create
+            .owner
+            .or_else(|| create.client.signer.as_ref().and_then(|s| s.address()))
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+        check_witness_url(&create.url)?;
+
+        let (account, chain_parameters) = tokio::try_join!(
+            create.client.provider.get_account(owner),
+            create.client.provider.chain_parameters()
+        )?;
+        if account.is_witness {
+            return Err(Error::PreconditionFailed(format!(
+                "{owner} is already a witness"
+            )));
+        }
+        let upgrade_cost =
+            chain_parameters.account_upgrade_cost.unwrap_or_default();
+
+        let latest_block = create.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::WitnessCreateContract(
+                        WitnessCreateContract {
+                            owner_address: owner,
+                            url: create.url,
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default(),
+        );
+        PendingTransaction::new(
+            create.client,
+            transaction,
+            owner,
+            upgrade_cost,
+            Vec::new(),
+            create.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct WitnessUpdate<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    /// New witness website
+    #[builder(into)]
+    pub(super) url: Message,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: witness_update_builder::IsComplete>
+    WitnessUpdateBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let update = self.build_internal();
+        let owner = update
+            .owner
+            .or_else(|| update.client.signer.as_ref().and_then(|s| s.address()))
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+        check_witness_url(&update.url)?;
+
+        let account = update.client.provider.get_account(owner).await?;
+        if !account.is_witness {
+            return Err(Error::PreconditionFailed(format!(
+                "{owner} is not a witness"
+            )));
+        }
+
+        let latest_block = update.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::WitnessUpdateContract(
+                        WitnessUpdateContract {
+                            owner_address: owner,
+                            update_url: update.url,
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default(),
+        );
+        PendingTransaction::new(
+            update.client,
+            transaction,
+            owner,
+            Trx::ZERO,
+            Vec::new(),
+            update.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct UpdateBrokerage<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    /// Percent of rewards kept by the witness, 0..=100
+    pub(super) brokerage: i32,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: update_brokerage_builder::IsComplete>
+    UpdateBrokerageBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let update = self.build_internal();
+        let owner = update
+            .owner
+            .or_else(|| update.client.signer.as_ref().and_then(|s| s.address()))
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+        if !(0..=100).contains(&update.brokerage) {
+            return Err(Error::InvalidInput(
+                "brokerage must be in range 0..=100".into(),
+            ));
+        }
+
+        let account = update.client.provider.get_account(owner).await?;
+        if !account.is_witness {
+            return Err(Error::PreconditionFailed(format!(
+                "{owner} is not a witness"
+            )));
+        }
+
+        let latest_block = update.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::UpdateBrokerageContract(
+                        UpdateBrokerageContract {
+                            owner_address: owner,
+                            brokerage: update.brokerage,
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default()
+        );
+        PendingTransaction::new(
+            update.client,
+            transaction,
+            owner,
+            Trx::ZERO,
+            Vec::new(),
+            update.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct ProposalCreate<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    #[builder(field)]
+    pub(super) parameters: HashMap<ChainParameter, i64>,
+    pub(super) owner: Option<TronAddress>,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: proposal_create_builder::State>
+    ProposalCreateBuilder<'a, P, S, State>
+{
+    /// Propose `value` for `parameter`, can be called multiple times
+    pub fn parameter(mut self, parameter: ChainParameter, value: i64) -> Self {
+        self.parameters.insert(parameter, value);
+        self
+    }
+}
+
+impl<'a, P, S, State: proposal_create_builder::IsComplete>
+    ProposalCreateBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    /// Only witnesses can create proposals
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let create = self.build_internal();
+        let owner = create
+            .owner
+            .or_else(|| create.client.signer.as_ref().and_then(|s| s.address()))
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+        if create.parameters.is_empty() {
+            return Err(Error::InvalidInput(
+                "proposal has no parameters".into(),
+            ));
+        }
+
+        let account = create.client.provider.get_account(owner).await?;
+        if !account.is_witness {
+            return Err(Error::PreconditionFailed(format!(
+                "{owner} is not a witness"
+            )));
+        }
+
+        let latest_block = create.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::ProposalCreateContract(
+                        ProposalCreateContract {
+                            owner_address: owner,
+                            parameters: create
+                                .parameters
+                                .into_iter()
+                                .map(|(p, v)| (p.id(), v))
+                                .collect(),
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default()
+        );
+        PendingTransaction::new(
+            create.client,
+            transaction,
+            owner,
+            Trx::ZERO,
+            Vec::new(),
+            create.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct ProposalApprove<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    pub(super) proposal_id: i64,
+    /// `false` removes an earlier approval
+    #[builder(default = true)]
+    pub(super) approve: bool,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: proposal_approve_builder::IsComplete>
+    ProposalApproveBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let approve = self.build_internal();
+        let owner = approve
+            .owner
+            .or_else(|| {
+                approve.client.signer.as_ref().and_then(|s| s.address())
+            })
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+
+        let (account, proposal) = tokio::try_join!(
+            approve.client.provider.get_account(owner),
+            approve
+                .client
+                .provider
+                .get_proposal_by_id(approve.proposal_id)
+        )?;
+        if !account.is_witness {
+            return Err(Error::PreconditionFailed(format!(
+                "{owner} is not a witness"
+            )));
+        }
+        if proposal.state != ProposalState::Pending
+            || proposal.expiration_time <= OffsetDateTime::now_utc()
+        {
+            return Err(Error::PreconditionFailed(format!(
+                "proposal {} is not pending",
+                approve.proposal_id
+            )));
+        }
+        if proposal.approvals.contains(&owner) == approve.approve {
+            return Err(Error::PreconditionFailed(format!(
+                "approval of {owner} is already {}",
+                approve.approve
+            )));
+        }
+
+        let latest_block = approve.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::ProposalApproveContract(
+                        ProposalApproveContract {
+                            owner_address: owner,
+                            proposal_id: approve.proposal_id,
+                            is_add_approval: approve.approve,
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default()
+        );
+        PendingTransaction::new(
+            approve.client,
+            transaction,
+            owner,
+            Trx::ZERO,
+            Vec::new(),
+            approve.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct ProposalDelete<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    pub(super) proposal_id: i64,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: proposal_delete_builder::IsComplete>
+    ProposalDeleteBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    /// Only the proposer can delete a pending proposal
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let delete = self.build_internal();
+        let owner = delete
+            .owner
+            .or_else(|| delete.client.signer.as_ref().and_then(|s| s.address()))
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+
+        let proposal = delete
+            .client
+            .provider
+            .get_proposal_by_id(delete.proposal_id)
+            .await?;
+        if proposal.proposer_address != owner {
+            return Err(Error::PreconditionFailed(format!(
+                "{owner} is not the proposer"
+            )));
+        }
+        if proposal.state != ProposalState::Pending {
+            return Err(Error::PreconditionFailed(format!(
+                "proposal {} is not pending",
+                delete.proposal_id
+            )));
+        }
+
+        let latest_block = delete.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::ProposalDeleteContract(
+                        ProposalDeleteContract {
+                            owner_address: owner,
+                            proposal_id: delete.proposal_id,
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default()
+        );
+        PendingTransaction::new(
+            delete.client,
+            transaction,
+            owner,
+            Trx::ZERO,
+            Vec::new(),
+            delete.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+/// Balance of a TRC-10 token or TRX (`"_"`) in the smallest units
+fn token_balance(account: &Account, token_id: &str) -> i64 {
+    if token_id == TRX_TOKEN_ID {
+        account.balance.to_sun()
+    } else {
+        account.asset_v2.get(token_id).copied().unwrap_or_default()
+    }
+}
+
+/// TRX moved by `quant` of `token_id`
+fn token_trx(token_id: &str, quant: i64) -> Trx {
+    if token_id == TRX_TOKEN_ID {
+        Trx::from_sun(quant)
+    } else {
+        Trx::ZERO
+    }
+}
+
+fn check_token_balance(
+    account: &Account,
+    token_id: &str,
+    quant: i64,
+) -> Result<()> {
+    // TRX is checked with fees by the pending transaction
+    if token_id == TRX_TOKEN_ID {
+        return Ok(());
+    }
+    let balance = token_balance(account, token_id);
+    if balance < quant {
+        return Err(Error::PreconditionFailed(format!(
+            "insufficient token {token_id} balance: {balance}, required: {quant}"
+        )));
+    }
+    Ok(())
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct ExchangeCreate<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    /// TRC-10 asset id or `"_"` for TRX
+    #[builder(into)]
+    pub(super) first_token_id: String,
+    pub(super) first_token_balance: i64,
+    #[builder(into)]
+    pub(super) second_token_id: String,
+    pub(super) second_token_balance: i64,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: exchange_create_builder::IsComplete>
+    ExchangeCreateBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    /// Creating an exchange burns `getExchangeCreateFee` TRX
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let create = self.build_internal();
+        let owner = create
+            .owner
+            .or_else(|| create.client.signer.as_ref().and_then(|s| s.address()))
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+        if create.first_token_id == create.second_token_id {
+            return Err(Error::InvalidInput(
+                "exchange tokens must differ".into(),
+            ));
+        }
+        if create.first_token_balance <= 0 || create.second_token_balance <= 0 {
+            return Err(Error::InvalidInput(
+                "exchange balances must be positive".into(),
+            ));
+        }
+
+        let (account, chain_parameters) = tokio::try_join!(
+            create.client.provider.get_account(owner),
+            create.client.provider.chain_parameters()
+        )?;
+        check_token_balance(
+            &account,
+            &create.first_token_id,
+            create.first_token_balance,
+        )?;
+        check_token_balance(
+            &account,
+            &create.second_token_id,
+            create.second_token_balance,
+        )?;
+        let base_trx = chain_parameters.exchange_create_fee.unwrap_or_default()
+            + token_trx(&create.first_token_id, create.first_token_balance)
+            + token_trx(&create.second_token_id, create.second_token_balance);
+
+        let latest_block = create.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::ExchangeCreateContract(
+                        ExchangeCreateContract {
+                            owner_address: owner,
+                            first_token_id: create.first_token_id.as_str().into(),
+                            first_token_balance: create.first_token_balance,
+                            second_token_id: create
+                                .second_token_id
+                                .as_str()
+                                .into(),
+                            second_token_balance: create.second_token_balance,
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default()
+        );
+        PendingTransaction::new(
+            create.client,
+            transaction,
+            owner,
+            base_trx,
+            Vec::new(),
+            create.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct ExchangeInject<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    pub(super) exchange_id: i64,
+    /// Token deposited, the opposite token is deposited at current price
+    #[builder(into)]
+    pub(super) token_id: String,
+    pub(super) quant: i64,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: exchange_inject_builder::IsComplete>
+    ExchangeInjectBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    /// Only the creator can inject
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let inject = self.build_internal();
+        let owner = inject
+            .owner
+            .or_else(|| inject.client.signer.as_ref().and_then(|s| s.address()))
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+        if inject.quant <= 0 {
+            return Err(Error::InvalidInput("quant must be positive".into()));
+        }
+
+        let (account, exchange) = tokio::try_join!(
+            inject.client.provider.get_account(owner),
+            inject
+                .client
+                .provider
+                .get_exchange_by_id(inject.exchange_id)
+        )?;
+        if exchange.creator_address != owner {
+            return Err(Error::PreconditionFailed(format!(
+                "{owner} is not the exchange creator"
+            )));
+        }
+        let (other_token, another) = exchange
+            .other_token(&inject.token_id)
+            .zip(exchange.paired_amount(&inject.token_id, inject.quant))
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "token {} is not traded on exchange {}",
+                    inject.token_id, inject.exchange_id
+                ))
+            })?;
+        check_token_balance(&account, &inject.token_id, inject.quant)?;
+        check_token_balance(&account, other_token, another)?;
+        let base_trx = token_trx(&inject.token_id, inject.quant)
+            + token_trx(other_token, another);
+
+        let latest_block = inject.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::ExchangeInjectContract(
+                        ExchangeInjectContract {
+                            owner_address: owner,
+                            exchange_id: inject.exchange_id,
+                            token_id: inject.token_id.as_str().into(),
+                            quant: inject.quant,
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default()
+        );
+        PendingTransaction::new(
+            inject.client,
+            transaction,
+            owner,
+            base_trx,
+            Vec::new(),
+            inject.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct ExchangeWithdraw<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    pub(super) exchange_id: i64,
+    /// Token withdrawn, the opposite token is withdrawn at current price
+    #[builder(into)]
+    pub(super) token_id: String,
+    pub(super) quant: i64,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: exchange_withdraw_builder::IsComplete>
+    ExchangeWithdrawBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    /// Only the creator can withdraw
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let withdraw = self.build_internal();
+        let owner = withdraw
+            .owner
+            .or_else(|| {
+                withdraw.client.signer.as_ref().and_then(|s| s.address())
+            })
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+        if withdraw.quant <= 0 {
+            return Err(Error::InvalidInput("quant must be positive".into()));
+        }
+
+        let exchange = withdraw
+            .client
+            .provider
+            .get_exchange_by_id(withdraw.exchange_id)
+            .await?;
+        if exchange.creator_address != owner {
+            return Err(Error::PreconditionFailed(format!(
+                "{owner} is not the exchange creator"
+            )));
+        }
+        let (balance, _) =
+            exchange.balances(&withdraw.token_id).ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "token {} is not traded on exchange {}",
+                    withdraw.token_id, withdraw.exchange_id
+                ))
+            })?;
+        if withdraw.quant > balance {
+            return Err(Error::PreconditionFailed(format!(
+                "exchange balance {balance} is less than {}",
+                withdraw.quant
+            )));
+        }
+
+        let latest_block = withdraw.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::ExchangeWithdrawContract(
+                        ExchangeWithdrawContract {
+                            owner_address: owner,
+                            exchange_id: withdraw.exchange_id,
+                            token_id: withdraw.token_id.as_str().into(),
+                            quant: withdraw.quant,
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default()
+        );
+        PendingTransaction::new(
+            withdraw.client,
+            transaction,
+            owner,
+            Trx::ZERO,
+            Vec::new(),
+            withdraw.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct ExchangeTransaction<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    pub(super) exchange_id: i64,
+    /// Token sold
+    #[builder(into)]
+    pub(super) token_id: String,
+    pub(super) quant: i64,
+    /// Least amount to receive, computed from the quote and
+    /// `slippage_bps` when not set
+    pub(super) expected: Option<i64>,
+    /// Tolerated slippage from the quote, 1 = 0.01%
+    #[builder(default = 50)]
+    pub(super) slippage_bps: u32,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: exchange_transaction_builder::IsComplete>
+    ExchangeTransactionBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    /// Node rejects the transaction if it would receive less than expected
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let trade = self.build_internal();
+        let owner = trade
+            .owner
+            .or_else(|| trade.client.signer.as_ref().and_then(|s| s.address()))
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+        if trade.quant <= 0 {
+            return Err(Error::InvalidInput("quant must be positive".into()));
+        }
+
+        let (account, exchange) = tokio::try_join!(
+            trade.client.provider.get_account(owner),
+            trade.client.provider.get_exchange_by_id(trade.exchange_id)
+        )?;
+        let quote =
+            exchange
+                .quote(&trade.token_id, trade.quant)
+                .ok_or_else(|| {
+                    Error::InvalidInput(format!(
+                        "token {} is not traded on exchange {}",
+                        trade.token_id, trade.exchange_id
+                    ))
+                })?;
+        let expected = trade
+            .expected
+            .unwrap_or_else(|| quote.min_received(trade.slippage_bps))
+            .max(1);
+        if quote.buy_quant < expected {
+            return Err(Error::PreconditionFailed(format!(
+                "quote {} is below expected {expected}",
+                quote.buy_quant
+            )));
+        }
+        check_token_balance(&account, &trade.token_id, trade.quant)?;
+
+        let latest_block = trade.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::ExchangeTransactionContract(
+                        ExchangeTransactionContract {
+                            owner_address: owner,
+                            exchange_id: trade.exchange_id,
+                            token_id: trade.token_id.as_str().into(),
+                            quant: trade.quant,
+                            expected,
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default()
+        );
+        PendingTransaction::new(
+            trade.client,
+            transaction,
+            owner,
+            token_trx(&trade.token_id, trade.quant),
+            Vec::new(),
+            trade.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct MarketSell<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    /// TRC-10 asset id or `"_"` for TRX
+    #[builder(into)]
+    pub(super) sell_token_id: String,
+    pub(super) sell_token_quantity: i64,
+    #[builder(into)]
+    pub(super) buy_token_id: String,
+    /// Least amount to receive for the whole order, sets the price
+    pub(super) buy_token_quantity: i64,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: market_sell_builder::IsComplete>
+    MarketSellBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    /// Order id and immediate fills are in the receipt, see
+    /// [`MarketFill`](crate::domain::market::MarketFill)
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let sell = self.build_internal();
+        let owner = sell
+            .owner
+            .or_else(|| sell.client.signer.as_ref().and_then(|s| s.address()))
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+        if sell.sell_token_id == sell.buy_token_id {
+            return Err(Error::InvalidInput("order tokens must differ".into()));
+        }
+        if sell.sell_token_quantity <= 0 || sell.buy_token_quantity <= 0 {
+            return Err(Error::InvalidInput(
+                "order quantities must be positive".into(),
+            ));
+        }
+
+        let (account, chain_parameters) = tokio::try_join!(
+            sell.client.provider.get_account(owner),
+            sell.client.provider.chain_parameters()
+        )?;
+        check_token_balance(
+            &account,
+            &sell.sell_token_id,
+            sell.sell_token_quantity,
+        )?;
+        let base_trx = chain_parameters.market_sell_fee.unwrap_or_default()
+            + token_trx(&sell.sell_token_id, sell.sell_token_quantity);
+
+        let latest_block = sell.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::MarketSellAssetContract(
+                        MarketSellAssetContract {
+                            owner_address: owner,
+                            sell_token_id: sell.sell_token_id.as_str().into(),
+                            sell_token_quantity: sell.sell_token_quantity,
+                            buy_token_id: sell.buy_token_id.as_str().into(),
+                            buy_token_quantity: sell.buy_token_quantity,
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default()
+        );
+        PendingTransaction::new(
+            sell.client,
+            transaction,
+            owner,
+            base_trx,
+            Vec::new(),
+            sell.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct MarketCancel<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    pub(super) owner: Option<TronAddress>,
+    pub(super) order_id: Hash32,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, State: market_cancel_builder::IsComplete>
+    MarketCancelBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+{
+    /// Only active orders of the owner can be canceled
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let cancel = self.build_internal();
+        let owner = cancel
+            .owner
+            .or_else(|| cancel.client.signer.as_ref().and_then(|s| s.address()))
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+
+        let (order, chain_parameters) = tokio::try_join!(
+            cancel
+                .client
+                .provider
+                .get_market_order_by_id(cancel.order_id),
+            cancel.client.provider.chain_parameters()
+        )?;
+        if order.owner_address != owner {
+            return Err(Error::PreconditionFailed(format!(
+                "{owner} is not the order owner"
+            )));
+        }
+        if order.state != MarketOrderState::Active {
+            return Err(Error::PreconditionFailed(format!(
+                "order is {:?}",
+                order.state
+            )));
+        }
+
+        let latest_block = cancel.client.provider.get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::MarketCancelOrderContract(
+                        MarketCancelOrderContract {
+                            owner_address: owner,
+                            order_id: Vec::<u8>::from(cancel.order_id).into(),
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default()
+        );
+        PendingTransaction::new(
+            cancel.client,
+            transaction,
+            owner,
+            chain_parameters.market_cancel_fee.unwrap_or_default(),
+            Vec::new(),
+            cancel.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct LogQuery<'a, P, S> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    /// Only logs emitted by the contract
+    pub(super) contract: Option<TronAddress>,
+    /// Only logs with the first topic, e.g. `Transfer::SIGNATURE_HASH`
+    pub(super) topic0: Option<B256>,
+    pub(super) from_block: i64,
+    /// Latest block if not set
+    pub(super) to_block: Option<i64>,
+    /// Blocks per page
+    #[builder(default = 100)]
+    pub(super) page_size: i64,
+    /// Blocks fetched concurrently
+    #[builder(default = 8)]
+    pub(super) concurrency: usize,
+}
+
+impl<'a, P, S> LogQuery<'a, P, S>
+where
+    P: TronProvider,
+{
+    fn matches(&self, address: TronAddress, log: &Log) -> bool {
+        self.contract.is_none_or(|c| c == address)
+            && self.topic0.is_none_or(|topic0| {
+                log.topics.first().is_some_and(|t| t[..] == topic0[..])
+            })
+    }
+    async fn page(&self, from_block: i64, to_block: i64) -> Result<LogPage> {
+        let provider = &self.client.provider;
+        let infos: Vec<Vec<TransactionInfo>> =
+            futures::stream::iter(from_block..=to_block)
+                .map(|n| provider.get_transaction_info_by_block_num(n))
+                .buffered(self.concurrency.max(1))
+                .try_collect()
+                .await?;
+
+        let logs = infos
+            .iter()
+            .flatten()
+            .flat_map(TransactionInfo::log_entries)
+            .filter(|entry| self.matches(entry.address, &entry.event))
+            .collect();
+        Ok(LogPage {
+            from_block,
+            to_block,
+            logs,
+        })
+    }
+}
+
+impl<'a, P, S, State: log_query_builder::IsComplete>
+    LogQueryBuilder<'a, P, S, State>
+where
+    P: TronProvider,
+{
+    /// Matching logs page by page, in block order
+    pub fn pages(self) -> impl Stream<Item = Result<LogPage>> + 'a
+    where
+        S: 'a,
+    {
+        let query = self.build_internal();
+        futures::stream::try_unfold(
+            (query, None),
+            |(query, ranges)| async move {
+                let mut ranges = match ranges {
+                    Some(ranges) => ranges,
+                    None => {
+                        let head = || async {
+                            let block =
+                                query.client.provider.get_now_block().await?;
+                            Ok(block.block_header.raw_data.number)
+                        };
+                        page_ranges(
+                            query.from_block,
+                            query.to_block,
+                            query.page_size,
+                            head,
+                        )
+                        .await?
+                    }
+                };
+                let Some((from_block, page_end)) = ranges.next() else {
+                    return Ok(None);
+                };
+                let page = query.page(from_block, page_end).await?;
+                Ok(Some((page, (query, Some(ranges)))))
+            },
+        )
+    }
+    /// All events `E` in the range
+    pub async fn decode<E: SolEvent>(self) -> Result<Vec<LogEntry<E>>>
+    where
+        S: 'a,
+    {
+        self.pages()
+            .map_ok(|page| page.decode::<E>())
+            .try_concat()
+            .await
+    }
+}
+
+/// Inclusive block ranges of `page_size` blocks, up to the head by default
+async fn page_ranges<F, Fut>(
+    from_block: i64,
+    to_block: Option<i64>,
+    page_size: i64,
+    head: F,
+) -> Result<impl Iterator<Item = (i64, i64)> + Send>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<i64>>,
+{
+    if from_block < 0 {
+        return Err(Error::InvalidInput(
+            "from_block must not be negative".into(),
+        ));
+    }
+    let to_block = match to_block {
+        Some(to_block) => to_block,
+        None => head().await?,
+    };
+    let page_size = page_size.max(1);
+    let step = usize::try_from(page_size).unwrap_or(usize::MAX);
+    Ok((from_block..=to_block)
+        .step_by(step)
+        .map(move |page_start| {
+            (
+                page_start,
+                page_start.saturating_add(page_size - 1).min(to_block),
+            )
+        }))
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct EventStream<'a, P, S, E> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    /// Only events emitted by the contract
+    pub(super) address: Option<TronAddress>,
+    /// First block to scan, latest block if not set
+    pub(super) from_block: Option<i64>,
+    #[builder(default = std::time::Duration::from_secs(3))]
+    pub(super) poll_interval: std::time::Duration,
+    #[builder(skip)]
+    pub(super) _event: std::marker::PhantomData<E>,
+}
+
+impl<'a, P, S, E, State: event_stream_builder::IsComplete>
+    EventStreamBuilder<'a, P, S, E, State>
+where
+    P: TronProvider + Clone + Send + Sync + 'static,
+    S: PrehashSigner + Clone + Send + Sync + 'static,
+    S::Error: std::fmt::Debug,
+    E: SolEvent + Send + 'static,
+{
+    /// Decoded events of new blocks. Receipts are fetched for every block,
+    /// so events emitted through other contracts or `transferFrom` are
+    /// caught too.
+    pub async fn stream(
+        self,
+    ) -> Result<
+        impl Stream<Item = std::result::Result<ContractEvent<E>, ListenerError>>,
+    > {
+        let events = self.build_internal();
+        let block_cache = start_block_cache(events.from_block).await?;
+        let address = events.address;
+        let listener = Listener::new_with_block_cache(
+            events.client.clone(),
+            events.poll_interval,
+            block_cache,
+        )
+        .with_receipts();
+
+        let stream = listener.event_stream().flat_map(move |event| {
+            futures::stream::iter(match event {
+                ListenerEvent::Block(Ok(block)) => {
+                    block_events::<E>(&block, address)
+                        .into_iter()
+                        .map(|entry| Ok(ContractEvent::Log(entry)))
+                        .collect()
+                }
+                ListenerEvent::Block(Err(e)) => vec![Err(e)],
+                ListenerEvent::Reorg(reorg) => {
+                    vec![Ok(ContractEvent::Reorg(reorg))]
+                }
+            })
+        });
+        Ok(stream)
+    }
+}
+
+/// Item of [`EventStreamBuilder::stream`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContractEvent<E> {
+    Log(LogEntry<E>),
+    /// Logs of blocks `from..=to` were reverted, logs of the canonical
+    /// blocks follow
+    Reorg(Reorg),
+}
+
+/// Block cache to resume scanning right before `from_block`
+async fn start_block_cache(
+    from_block: Option<i64>,
+) -> Result<InMemoryBlockCache> {
+    let block_cache = InMemoryBlockCache::default();
+    if let Some(from_block) = from_block {
+        if from_block < 0 {
+            return Err(Error::InvalidInput(
+                "from_block must not be negative".into(),
+            ));
+        }
+        // Genesis has no contract events, and -1 reads as unset
+        block_cache
+            .store_latest_seen_block(from_block.max(1) - 1)
+            .await?;
+    }
+    Ok(block_cache)
+}
+
+/// Events `E` in the receipts of `block`, emitted by `address` if set
+fn block_events<E: SolEvent>(
+    block: &BlockExtention,
+    address: Option<TronAddress>,
+) -> Vec<LogEntry<E>> {
+    block
+        .transactions
+        .iter()
+        .filter_map(|tx| tx.info.as_ref())
+        .flat_map(TransactionInfo::log_entries)
+        .filter(|e| address.is_none_or(|a| a == e.address))
+        .filter_map(|entry| entry.decode::<E>())
+        .collect()
+}
+
+#[derive(bon::Builder)]
+#[builder(start_fn = with_client_and_call)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+pub struct WriteContract<'a, P, S, C> {
+    #[builder(start_fn)]
+    pub(super) client: &'a Client<P, S>,
+    #[builder(start_fn)]
+    pub(super) call: C,
+    pub(super) contract: TronAddress,
+    pub(super) owner: Option<TronAddress>,
+    /// TRX sent with the call
+    pub(super) call_value: Option<Trx>,
+    /// TRC-10 asset id and amount sent with the call
+    #[builder(with = |token_id: i64, amount: i64| (token_id, amount))]
+    pub(super) token: Option<(i64, i64)>,
+    /// Address receiving value from the call, checked for activation fee
+    pub(super) recipient: Option<TronAddress>,
+    pub(super) memo: Option<Message>,
+    #[builder(default = true)]
+    pub(super) can_spend_trx_for_fee: bool,
+}
+
+impl<'a, P, S, C, State: write_contract_builder::IsComplete>
+    WriteContractBuilder<'a, P, S, C, State>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+    Error: From<S::Error>,
+    C: AbiEncode,
+{
+    pub async fn build<M>(self) -> Result<PendingTransaction<'a, P, S, M>> {
+        let write = self.build_internal();
+        let owner = write
+            .owner
+            .or_else(|| write.client.signer.as_ref().and_then(|s| s.address()))
+            .ok_or_else(|| Error::Unexpected(eyre!("missing owner address")))?;
+        let call_value = write.call_value.unwrap_or_default();
+        let (token_id, call_token_value) = write.token.unwrap_or_default();
+        if call_token_value < 0 {
+            return Err(Error::InvalidInput(
+                "token amount must not be negative".into(),
+            ));
+        }
+        if call_token_value > 0 {
+            let account = write.client.provider.get_account(owner).await?;
+            check_token_balance(
+                &account,
+                &token_id.to_string(),
+                call_token_value,
+            )?;
+        }
+
+        let (latest_block, fee_parameters) = tokio::try_join!(
+            write.client.provider.get_now_block(),
+            write.client.fee_parameters()
+        )?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type:
+                    crate::domain::contract::ContractType::TriggerSmartContract(
+                        TriggerSmartContract {
+                            owner_address: owner,
+                            contract_address: write.contract,
+                            call_value,
+                            data: write.call.encode().into(),
+                            call_token_value: Trx::from_sun(call_token_value),
+                            token_id,
+                        },
+                    ),
+                ..Default::default()
+            },
+            &latest_block,
+            write.memo.unwrap_or_default(),
+        );
+        let activation_checks = write
+            .recipient
+            .map(|address| ActivationFeeCheck {
+                address,
+                fee: fee_parameters.create_account_fee,
+            })
+            .into_iter()
+            .collect();
+        PendingTransaction::new(
+            write.client,
+            transaction,
+            owner,
+            call_value,
+            activation_checks,
+            write.can_spend_trx_for_fee,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloy_primitives::U256;
+    use alloy_sol_types::SolCall;
+    use time::Duration;
+
+    use super::*;
+    use crate::client::pending::AutoSigning;
+    use crate::contracts::trc20::Trc20;
+    use crate::contracts::trc20::Trc20::Erc20;
+    use crate::domain::block::{BlockHeader, RawBlockHeader};
+    use crate::domain::chain::ChainParameters;
+    use crate::domain::contract::ContractType;
+    use crate::domain::transaction::TransactionExtention;
+    use crate::protocol;
+    use crate::provider::mock::MockProvider;
+    use crate::signer::LocalSigner;
+
+    fn address(n: u8) -> TronAddress {
+        let mut bytes = [n; 21];
+        bytes[0] = 0x41;
+        TronAddress::try_from(bytes.as_slice()).unwrap_or_default()
+    }
+
+    fn asset(now: OffsetDateTime) -> AssetIssueContract {
+        AssetIssueContract {
+            id: "1000001".into(),
+            owner_address: address(1),
+            name: "Token".into(),
+            abbr: "TKN".into(),
+            total_supply: 1_000_000,
+            trx_num: Trx::from_sun(1),
+            num: 1,
+            start_time: now + Duration::days(1),
+            end_time: now + Duration::days(2),
+            url: "https://example.com".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn asset_issue_is_validated() {
+        let now = OffsetDateTime::now_utc();
+        assert!(check_asset_issue(&asset(now), now).is_ok());
+
+        let invalid = [
+            AssetIssueContract {
+                url: Message::default(),
+                ..asset(now)
+            },
+            AssetIssueContract {
+                num: 0,
+                ..asset(now)
+            },
+            AssetIssueContract {
+                precision: MAX_ASSET_PRECISION + 1,
+                ..asset(now)
+            },
+            AssetIssueContract {
+                start_time: now,
+                ..asset(now)
+            },
+            AssetIssueContract {
+                end_time: now + Duration::days(1),
+                ..asset(now)
+            },
+        ];
+        for asset in invalid {
+            assert!(matches!(
+                check_asset_issue(&asset, now),
+                Err(Error::InvalidInput(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn participation_is_open_within_window() {
+        let now = OffsetDateTime::now_utc();
+        let asset = asset(now);
+        let open = now + Duration::hours(36);
+
+        assert!(check_participation(&asset, address(2), open).is_ok());
+        assert!(matches!(
+            check_participation(&asset, address(1), open),
+            Err(Error::InvalidInput(_))
+        ));
+        for at in [now, asset.end_time] {
+            assert!(matches!(
+                check_participation(&asset, address(2), at),
+                Err(Error::PreconditionFailed(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn log_pages_cover_range() {
+        let head = || async { Ok(250) };
+        let pages = |from_block, to_block, page_size| async move {
+            page_ranges(from_block, to_block, page_size, head)
+                .await
+                .map(Iterator::collect::<Vec<_>>)
+        };
+
+        assert!(matches!(
+            pages(10, Some(35), 10).await,
+            Ok(p) if p == [(10, 19), (20, 29), (30, 35)]
+        ));
+        // Latest block is resolved once, page size is at least a block
+        assert!(matches!(
+            pages(248, None, 0).await,
+            Ok(p) if p == [(248, 248), (249, 249), (250, 250)]
+        ));
+        assert!(matches!(pages(251, None, 100).await, Ok(p) if p.is_empty()));
+        assert!(matches!(
+            pages(-1, Some(10), 100).await,
+            Err(Error::InvalidInput(_))
+        ));
+
+        let failing_head =
+            || async { Err(Error::Unexpected(eyre!("head is not reachable"))) };
+        assert!(page_ranges(0, Some(10), 100, failing_head).await.is_ok());
+    }
+
+    fn transfer_log(token: TronAddress, value: u64) -> Log {
+        let data = Erc20::Transfer {
+            from: alloy_primitives::Address::repeat_byte(2),
+            to: alloy_primitives::Address::repeat_byte(3),
+            value: U256::from(value),
+        }
+        .encode_log_data();
+        // `to_vec` of `ContractConstructorParam` would ABI encode
+        Log {
+            address: alloy_primitives::Address::from(token).as_slice().into(),
+            topics: data.topics().iter().map(|t| t.as_slice().into()).collect(),
+            data: data.data.as_ref().into(),
+        }
+    }
+
+    fn receipt_tx(logs: Option<Vec<Log>>) -> TransactionExtention {
+        let info = logs.and_then(|log| {
+            let info = protocol::TransactionInfo::default();
+            let info = TransactionInfo::try_from(info).ok()?;
+            Some(TransactionInfo { log, ..info })
+        });
+        TransactionExtention {
+            transaction: None,
+            txid: Default::default(),
+            constant_result: Vec::new(),
+            energy_used: 0,
+            energy_penalty: 0,
+            result: None,
+            logs: Vec::new(),
+            internal_transactions: Vec::new(),
+            info,
+        }
+    }
+
+    #[tokio::test]
+    async fn event_stream_starts_before_from_block() {
+        let last_seen = |from_block| async move {
+            let cache = start_block_cache(from_block).await?;
+            cache.load_latest_seen_block().await
+        };
+
+        assert!(matches!(last_seen(None).await, Ok(None)));
+        assert!(matches!(last_seen(Some(100)).await, Ok(Some(99))));
+        // Genesis is skipped rather than read as an unset cache
+        assert!(matches!(last_seen(Some(0)).await, Ok(Some(0))));
+        assert!(matches!(
+            last_seen(Some(-1)).await,
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    fn block(transactions: Vec<TransactionExtention>) -> BlockExtention {
+        BlockExtention {
+            transactions,
+            block_header: BlockHeader {
+                raw_data: RawBlockHeader {
+                    timestamp: OffsetDateTime::UNIX_EPOCH,
+                    tx_trie_root: Hash32::default(),
+                    parent_hash: Hash32::default(),
+                    number: 1,
+                    witness_id: 0,
+                    witness_address: TronAddress::default(),
+                    version: 0,
+                    account_state_root: Hash32::default(),
+                },
+                witness_signature: None,
+                pq_auth_sig: None,
+            },
+            blockid: Hash32::default(),
+        }
+    }
+
+    #[test]
+    fn block_events_are_read_from_receipts() {
+        let token = address(1);
+        let other = address(2);
+        let block = block(vec![
+            receipt_tx(Some(vec![
+                transfer_log(token, 1),
+                transfer_log(other, 2),
+            ])),
+            receipt_tx(None),
+            receipt_tx(Some(vec![transfer_log(token, 3)])),
+        ]);
+
+        let values = |address| {
+            block_events::<Erc20::Transfer>(&block, address)
+                .into_iter()
+                .map(|e| (e.log_index, e.event.value.to::<u64>()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(Some(token)), [(0, 1), (0, 3)]);
+        assert_eq!(values(None), [(0, 1), (1, 2), (0, 3)]);
+        assert!(block_events::<Erc20::Approval>(&block, None).is_empty());
+    }
+
+    #[test]
+    fn asset_balance_is_checked() {
+        let account = Account {
+            asset_v2: HashMap::from([("1000001".to_string(), 10)]),
+            ..Default::default()
+        };
+
+        assert!(check_token_balance(&account, "1000001", 10).is_ok());
+        assert!(matches!(
+            check_token_balance(&account, "1000001", 11),
+            Err(Error::PreconditionFailed(_))
+        ));
+        assert!(matches!(
+            check_token_balance(&account, "1000002", 1),
+            Err(Error::PreconditionFailed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn write_contract_encodes_call() {
+        let provider = MockProvider::new().await;
+        let raw = [
+            ("getCreateAccountFee", 100_000),
+            ("getTransactionFee", 1_000),
+            ("getCreateNewAccountFeeInSystemContract", 1_000_000),
+            ("getEnergyFee", 420),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+        provider.update(|s| {
+            s.blocks.insert(1, block(Vec::new()));
+            s.chain_parameters = ChainParameters::from_raw(raw).ok();
+            s.estimate_energy = Some(20_000);
+        });
+        let client = Client::<_, LocalSigner>::builder()
+            .provider(provider.clone())
+            .build();
+        let call = Trc20::transferCall {
+            recipient: address(3),
+            amount: U256::from(5),
+        };
+        let write = |call| {
+            client
+                .write_contract(call)
+                .contract(address(2))
+                .owner(address(1))
+                .call_value(Trx::from_sun(7))
+                .build::<AutoSigning>()
+        };
+
+        let Ok(pending) = write(call.clone()).await else {
+            panic!("transaction is built");
+        };
+        let transaction = pending.transaction();
+        let Some(ContractType::TriggerSmartContract(trigger)) =
+            transaction.raw.contract.first().map(|c| &c.contract_type)
+        else {
+            panic!("contract call");
+        };
+        let data = call.clone().encode();
+        assert_eq!(data[..4], Erc20::transferCall::SELECTOR);
+        assert_eq!(trigger.data, data.into());
+        assert_eq!(trigger.owner_address, address(1));
+        assert_eq!(trigger.contract_address, address(2));
+        assert_eq!(trigger.call_value, Trx::from_sun(7));
+        // 1.5 times the estimate at the energy price
+        assert_eq!(transaction.raw.fee_limit, Trx::from_sun(30_000 * 420));
+
+        // Fixed cap of a contract call without an estimate
+        provider.update(|s| s.estimate_energy = None);
+        let Ok(pending) = write(call).await else {
+            panic!("transaction is built");
+        };
+        assert_eq!(pending.transaction().raw.fee_limit, trx!(200.0 TRX));
+    }
+}
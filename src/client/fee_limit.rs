@@ -0,0 +1,311 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use crate::domain::address::TronAddress;
+use crate::domain::contract::{Contract, ContractType};
+use crate::domain::estimate::EnergyEstimateMethod;
+use crate::domain::transaction::TransactionInfo;
+use crate::domain::trx::Trx;
+use crate::trx;
+
+/// Data available to a [`FeeLimitStrategy`] when a fee limit is computed
+#[derive(Debug, Clone)]
+pub struct FeeLimitContext {
+    /// First contract of the transaction
+    pub contract: Option<Contract>,
+    /// Energy estimated for the transaction, if estimation succeeded
+    pub estimated_energy: Option<i64>,
+    /// How `estimated_energy` was obtained
+    pub estimate_method: Option<EnergyEstimateMethod>,
+    /// Current energy price (per unit), `None` if chain parameters
+    /// couldn't be fetched
+    pub energy_price: Option<Trx>,
+}
+
+impl FeeLimitContext {
+    /// Address of the called smart contract, if any
+    pub fn contract_address(&self) -> Option<TronAddress> {
+        self.contract.as_ref().and_then(|c| c.contract_address())
+    }
+    /// Fixed fee limit used when energy can't be estimated:
+    /// 1000 TRX for deployments, 200 TRX for contract calls, 50 TRX otherwise.
+    pub fn fallback(&self) -> Trx {
+        match self.contract.as_ref().map(|c| &c.contract_type) {
+            Some(ContractType::CreateSmartContract(_)) => trx!(1000.0 TRX),
+            Some(ContractType::TriggerSmartContract(_)) => trx!(200.0 TRX),
+            _ => trx!(50.0 TRX),
+        }
+    }
+    fn energy_fee(&self, energy: i64) -> Option<Trx> {
+        self.energy_price.map(|price| price * energy)
+    }
+}
+
+/// Policy that decides `fee_limit` for every transaction built by a [`Client`].
+///
+/// [`Client`]: crate::client::Client
+pub trait FeeLimitStrategy: Send + Sync {
+    fn fee_limit(&self, ctx: &FeeLimitContext) -> Trx;
+    /// Called with the receipt of every successful transaction awaited by
+    /// [`PendingTransaction::broadcast_get_receipt`]
+    ///
+    /// [`PendingTransaction::broadcast_get_receipt`]: crate::client::pending::PendingTransaction::broadcast_get_receipt
+    fn observe(&self, _info: &TransactionInfo) {}
+}
+
+impl<F> FeeLimitStrategy for F
+where
+    F: Fn(&FeeLimitContext) -> Trx + Send + Sync,
+{
+    fn fee_limit(&self, ctx: &FeeLimitContext) -> Trx {
+        self(ctx)
+    }
+}
+
+impl<T: FeeLimitStrategy + ?Sized> FeeLimitStrategy for Arc<T> {
+    fn fee_limit(&self, ctx: &FeeLimitContext) -> Trx {
+        (**self).fee_limit(ctx)
+    }
+    fn observe(&self, info: &TransactionInfo) {
+        (**self).observe(info)
+    }
+}
+
+/// Always uses the same fee limit
+#[derive(Debug, Clone, Copy)]
+pub struct FixedFeeLimit(pub Trx);
+
+impl FeeLimitStrategy for FixedFeeLimit {
+    fn fee_limit(&self, _: &FeeLimitContext) -> Trx {
+        self.0
+    }
+}
+
+/// `estimated_energy * factor * energy_price`, or [`FeeLimitContext::fallback`]
+/// if energy or its price is unknown. This is the default strategy with
+/// factor 1.5.
+#[derive(Debug, Clone, Copy)]
+pub struct EnergyMultiplier(pub f64);
+
+impl Default for EnergyMultiplier {
+    fn default() -> Self {
+        EnergyMultiplier(1.5)
+    }
+}
+
+impl FeeLimitStrategy for EnergyMultiplier {
+    fn fee_limit(&self, ctx: &FeeLimitContext) -> Trx {
+        ctx.estimated_energy
+            .and_then(|energy| {
+                ctx.energy_fee(((energy as f64) * self.0) as i64)
+            })
+            .unwrap_or_else(|| ctx.fallback())
+    }
+}
+
+/// Caps the fee limit produced by the inner strategy, it is never exceeded.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxFeeLimit<S> {
+    pub inner: S,
+    pub max: Trx,
+}
+
+impl<S> MaxFeeLimit<S> {
+    pub fn new(inner: S, max: Trx) -> Self {
+        MaxFeeLimit { inner, max }
+    }
+}
+
+impl<S: FeeLimitStrategy> FeeLimitStrategy for MaxFeeLimit<S> {
+    fn fee_limit(&self, ctx: &FeeLimitContext) -> Trx {
+        self.inner.fee_limit(ctx).min(self.max)
+    }
+    fn observe(&self, info: &TransactionInfo) {
+        self.inner.observe(info)
+    }
+}
+
+/// Uses a percentile of energy consumed by recent transactions to the same
+/// contract (but never less than the current estimate). Receipts awaited by
+/// the client are collected automatically, others can be added with
+/// [`PercentileFeeLimit::observe_energy`]. Until enough samples are known
+/// `fallback` strategy is used.
+#[derive(Clone)]
+pub struct PercentileFeeLimit<S = EnergyMultiplier> {
+    percentile: f64,
+    window: usize,
+    min_samples: usize,
+    fallback: S,
+    samples: Arc<Mutex<HashMap<TronAddress, VecDeque<i64>>>>,
+}
+
+impl PercentileFeeLimit<EnergyMultiplier> {
+    /// `percentile` should be in range [0, 100]
+    pub fn new(percentile: f64) -> Self {
+        PercentileFeeLimit {
+            percentile: percentile.clamp(0.0, 100.0),
+            window: 100,
+            min_samples: 10,
+            fallback: EnergyMultiplier::default(),
+            samples: Default::default(),
+        }
+    }
+}
+
+impl<S> PercentileFeeLimit<S> {
+    /// How many recent samples are kept per contract
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+    /// How many samples are required before percentile is used
+    pub fn with_min_samples(mut self, min_samples: usize) -> Self {
+        self.min_samples = min_samples.max(1);
+        self
+    }
+    pub fn with_fallback<NewS>(
+        self,
+        fallback: NewS,
+    ) -> PercentileFeeLimit<NewS> {
+        PercentileFeeLimit {
+            percentile: self.percentile,
+            window: self.window,
+            min_samples: self.min_samples,
+            fallback,
+            samples: self.samples,
+        }
+    }
+    /// Record energy consumed by a call to `contract`
+    pub fn observe_energy(&self, contract: TronAddress, energy: i64) {
+        let mut samples =
+            self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = samples.entry(contract).or_default();
+        entry.push_back(energy);
+        while entry.len() > self.window {
+            entry.pop_front();
+        }
+    }
+    fn percentile_energy(&self, contract: TronAddress) -> Option<i64> {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = samples.get(&contract)?;
+        if entry.len() < self.min_samples {
+            return None;
+        }
+        let mut sorted: Vec<i64> = entry.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = (self.percentile / 100.0 * (sorted.len() - 1) as f64).ceil();
+        sorted.get(rank as usize).copied()
+    }
+}
+
+impl<S: FeeLimitStrategy> FeeLimitStrategy for PercentileFeeLimit<S> {
+    fn fee_limit(&self, ctx: &FeeLimitContext) -> Trx {
+        ctx.contract_address()
+            .and_then(|a| self.percentile_energy(a))
+            .and_then(|energy| {
+                ctx.energy_fee(
+                    energy.max(ctx.estimated_energy.unwrap_or_default()),
+                )
+            })
+            .unwrap_or_else(|| self.fallback.fee_limit(ctx))
+    }
+    /// Records energy consumed by a confirmed contract call
+    fn observe(&self, info: &TransactionInfo) {
+        let Some(receipt) = info.receipt.as_ref() else {
+            return;
+        };
+        if info.contract_address == TronAddress::ZERO {
+            return;
+        }
+        self.observe_energy(info.contract_address, receipt.energy_usage_total);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::contract::TriggerSmartContract;
+
+    fn contract() -> TronAddress {
+        let mut bytes = [1; 21];
+        bytes[0] = 0x41;
+        TronAddress::try_from(bytes.as_slice()).unwrap_or_default()
+    }
+
+    fn ctx(estimated_energy: Option<i64>) -> FeeLimitContext {
+        FeeLimitContext {
+            contract: Some(Contract {
+                contract_type: ContractType::TriggerSmartContract(
+                    TriggerSmartContract {
+                        contract_address: contract(),
+                        ..Default::default()
+                    },
+                ),
+                ..Default::default()
+            }),
+            estimated_energy,
+            estimate_method: estimated_energy
+                .map(|_| EnergyEstimateMethod::EstimateEnergy),
+            energy_price: Some(Trx::from_sun(100)),
+        }
+    }
+
+    #[test]
+    fn fixed_and_multiplier() {
+        let fixed = FixedFeeLimit(trx!(10.0 TRX));
+        assert_eq!(fixed.fee_limit(&ctx(Some(1_000))), trx!(10.0 TRX));
+
+        let multiplier = EnergyMultiplier::default();
+        assert_eq!(
+            multiplier.fee_limit(&ctx(Some(1_000))),
+            Trx::from_sun(150_000)
+        );
+        // Unknown energy or price falls back to the fixed cap
+        assert_eq!(multiplier.fee_limit(&ctx(None)), trx!(200.0 TRX));
+        let no_price = FeeLimitContext {
+            energy_price: None,
+            ..ctx(Some(1_000))
+        };
+        assert_eq!(multiplier.fee_limit(&no_price), trx!(200.0 TRX));
+    }
+
+    #[test]
+    fn max_is_never_exceeded() {
+        let capped = MaxFeeLimit::new(EnergyMultiplier(2.0), trx!(5.0 TRX));
+        assert_eq!(capped.fee_limit(&ctx(None)), trx!(5.0 TRX));
+        assert_eq!(capped.fee_limit(&ctx(Some(1_000))), Trx::from_sun(200_000));
+    }
+
+    #[test]
+    fn percentile_of_observed_energy() {
+        let contract = contract();
+        let strategy = PercentileFeeLimit::new(90.0)
+            .with_min_samples(3)
+            .with_window(10);
+        // Not enough samples yet
+        strategy.observe_energy(contract, 1_000);
+        assert_eq!(
+            strategy.fee_limit(&ctx(Some(1_000))),
+            Trx::from_sun(150_000)
+        );
+
+        for energy in [2_000, 3_000, 4_000, 5_000] {
+            strategy.observe_energy(contract, energy);
+        }
+        assert_eq!(
+            strategy.fee_limit(&ctx(Some(1_000))),
+            Trx::from_sun(500_000)
+        );
+        // Never below the current estimate
+        assert_eq!(
+            strategy.fee_limit(&ctx(Some(9_000))),
+            Trx::from_sun(900_000)
+        );
+
+        // Old samples leave the window
+        for _ in 0..10 {
+            strategy.observe_energy(contract, 100);
+        }
+        assert_eq!(strategy.fee_limit(&ctx(None)), Trx::from_sun(10_000));
+    }
+}
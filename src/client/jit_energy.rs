@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use crate::client::stake::StakePlanner;
+use crate::domain::address::TronAddress;
+use crate::domain::contract::ResourceCode;
+use crate::domain::estimate::EnergyEstimate;
+use crate::domain::trx::Trx;
+
+/// Just-in-time energy: before an auto-signed contract call is broadcast
+/// with not enough energy, `pool` delegates the shortfall to the sender.
+/// Delegation is returned if the transaction fails to broadcast.
+#[derive(bon::Builder, Clone)]
+pub struct JitEnergy<S> {
+    /// Account with TRX frozen for energy
+    pub pool: TronAddress,
+    /// Signer of the `pool` account
+    pub signer: S,
+    /// Undelegate once the transaction is included in a block. `broadcast`
+    /// then waits for inclusion, up to `timeout`, before returning.
+    #[builder(default = false)]
+    pub undelegate: bool,
+    /// Minimal delegation, the node rejects delegations below 1 TRX
+    #[builder(default = Trx::from_sun(1_000_000))]
+    pub min_delegation: Trx,
+    /// Largest delegation for a single transaction, transactions needing
+    /// more pay for energy themselves
+    pub max_delegation: Option<Trx>,
+    /// How long to wait for the delegation and for the transaction to land
+    #[builder(default = Duration::from_secs(30))]
+    pub timeout: Duration,
+}
+
+impl<S> JitEnergy<S> {
+    /// TRX to delegate for `missing` energy. `None` if nothing is missing,
+    /// energy wasn't estimated (the fee limit is only an upper bound) or
+    /// the amount exceeds `max_delegation`.
+    pub fn delegation_amount(
+        &self,
+        estimate: Option<EnergyEstimate>,
+        missing: i64,
+        planner: &StakePlanner,
+    ) -> Option<Trx> {
+        estimate?;
+        if missing <= 0 {
+            return None;
+        }
+        let amount = planner
+            .staked_for(missing, ResourceCode::Energy)?
+            .max(self.min_delegation);
+        if self.max_delegation.is_some_and(|max| amount > max) {
+            return None;
+        }
+        Some(amount)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::estimate::EnergyEstimateMethod;
+
+    #[test]
+    fn delegates_known_shortfall_within_bounds() {
+        let planner = StakePlanner {
+            total_energy_limit: 180_000_000_000,
+            total_energy_weight: 18_000_000_000,
+            total_net_limit: 43_200_000_000,
+            total_net_weight: 26_000_000_000,
+        };
+        let jit = JitEnergy::builder()
+            .pool(TronAddress::ZERO)
+            .signer(())
+            .max_delegation(Trx::from_sun(10_000_000_000))
+            .build();
+        let estimate = Some(EnergyEstimate {
+            energy: 65_000,
+            method: EnergyEstimateMethod::EstimateEnergy,
+        });
+
+        // 10 energy per staked TRX
+        assert_eq!(
+            jit.delegation_amount(estimate, 65_000, &planner),
+            Some(Trx::from_sun(6_500_000_000))
+        );
+        assert_eq!(
+            jit.delegation_amount(estimate, 1, &planner),
+            Some(jit.min_delegation)
+        );
+        assert_eq!(jit.delegation_amount(estimate, 0, &planner), None);
+        // Above max_delegation
+        assert_eq!(jit.delegation_amount(estimate, 200_000, &planner), None);
+        // Energy derived from the fee limit
+        assert_eq!(jit.delegation_amount(None, 65_000, &planner), None);
+    }
+}
@@ -1,20 +1,38 @@
+use std::sync::Arc;
 use std::time::Duration;
 
-use eyre::eyre;
 use secrecy::SecretString;
+use time::OffsetDateTime;
 
 use crate::Result;
+use crate::contracts::revert::Revert;
+use crate::domain::Hash32;
 use crate::domain::account::AccountStatus;
 use crate::domain::address::TronAddress;
+use crate::domain::contract::TriggerSmartContract;
+use crate::domain::estimate::{FeeAttribution, FeeParameters};
+use crate::domain::exchange::{Exchange, ExchangeOutcome, ExchangeQuote};
+use crate::domain::market::{MarketFill, MarketOrder};
+use crate::domain::proposal::{Proposal, ProposalChange};
+use crate::domain::resource::ResourceProjection;
+use crate::domain::transaction::TxCode;
 use crate::domain::trx::Trx;
-use crate::listener::ListenerHandle;
+use crate::domain::witness::{WitnessRanking, WitnessRewardParams};
+use crate::error::Error;
+use crate::listener::{Confirmation, ListenerHandle};
 use crate::provider::TronProvider;
 use crate::signer::PrehashSigner;
 
 use builder::PermissionHandler;
+use fee_limit::{EnergyMultiplier, FeeLimitStrategy};
+use simulate::Simulation;
 
 pub mod builder;
+pub mod fee_limit;
+pub mod jit_energy;
 pub mod pending;
+pub mod simulate;
+pub mod stake;
 
 #[derive(Clone)]
 pub enum Auth {
@@ -25,6 +43,14 @@ pub enum Auth {
 pub struct Client<P, S> {
     pub(crate) provider: P,
     signer: Option<S>,
+    /// Policy used to set `fee_limit`, `EnergyMultiplier(1.5)` by default
+    #[builder(
+        with = |s: impl FeeLimitStrategy + 'static| Arc::new(s) as _,
+        default = Arc::new(EnergyMultiplier::default()),
+    )]
+    fee_limit_strategy: Arc<dyn FeeLimitStrategy>,
+    /// Opt-in energy delegation before auto-signed contract calls
+    jit_energy: Option<jit_energy::JitEnergy<S>>,
 }
 
 impl<P, S> Client<P, S>
@@ -38,15 +64,57 @@ where
     pub fn signer_address(&self) -> Option<TronAddress> {
         self.signer.as_ref().and_then(|s| s.address())
     }
-    pub fn with_signer<NewS>(self, s: NewS) -> Client<P, NewS> {
-        Client::<P, NewS> {
+    /// Fails if JIT energy is configured, its pool signer has the type of
+    /// the previous signer. Set the signer before
+    /// [`Client::with_jit_energy`].
+    pub fn with_signer<NewS>(self, s: NewS) -> Result<Client<P, NewS>> {
+        if self.jit_energy.is_some() {
+            return Err(Error::PreconditionFailed(
+                "signer can't be replaced after jit energy is configured"
+                    .into(),
+            ));
+        }
+        Ok(Client::<P, NewS> {
             provider: self.provider,
             signer: Some(s),
-        }
+            fee_limit_strategy: self.fee_limit_strategy,
+            jit_energy: None,
+        })
+    }
+    pub fn with_jit_energy(
+        mut self,
+        jit_energy: jit_energy::JitEnergy<S>,
+    ) -> Self {
+        self.jit_energy = Some(jit_energy);
+        self
+    }
+    pub fn with_fee_limit_strategy(
+        mut self,
+        strategy: impl FeeLimitStrategy + 'static,
+    ) -> Self {
+        self.fee_limit_strategy = Arc::new(strategy);
+        self
+    }
+    pub fn fee_limit_strategy(&self) -> &dyn FeeLimitStrategy {
+        self.fee_limit_strategy.as_ref()
     }
     pub fn send_trx(&self) -> builder::TransferBuilder<'_, P, S> {
         builder::Transfer::with_client(self)
     }
+    /// Transfer of a TRC-10 asset
+    pub fn transfer_asset(&self) -> builder::TransferAssetBuilder<'_, P, S> {
+        builder::TransferAsset::with_client(self)
+    }
+    /// Issue a TRC-10 asset
+    pub fn asset_issue(&self) -> builder::AssetIssueBuilder<'_, P, S> {
+        builder::AssetIssue::with_client(self)
+    }
+    /// Buy a TRC-10 asset during its participation window
+    pub fn participate_asset(
+        &self,
+    ) -> builder::ParticipateAssetBuilder<'_, P, S> {
+        builder::ParticipateAsset::with_client(self)
+    }
     pub fn trx_balance(&self) -> builder::TrxBalanceBuilder<'_, P, S> {
         builder::TrxBalance::with_client(self)
     }
@@ -56,6 +124,21 @@ where
     ) -> builder::ReadContractBuilder<'_, P, S, C> {
         builder::ReadContract::with_client_and_call(self, call)
     }
+    /// Historical logs over a block range
+    pub fn logs(&self) -> builder::LogQueryBuilder<'_, P, S> {
+        builder::LogQuery::with_client(self)
+    }
+    /// Live stream of contract events `E` and the reorgs that revert them
+    pub fn events<E>(&self) -> builder::EventStreamBuilder<'_, P, S, E> {
+        builder::EventStream::with_client(self)
+    }
+    /// State changing call of any contract method
+    pub fn write_contract<C>(
+        &self,
+        call: C,
+    ) -> builder::WriteContractBuilder<'_, P, S, C> {
+        builder::WriteContract::with_client_and_call(self, call)
+    }
     pub fn freeze_balance(&self) -> builder::FreezeBalanceBuilder<'_, P, S> {
         builder::FreezeBalance::with_client(self)
     }
@@ -80,6 +163,60 @@ where
     ) -> builder::WithdrawUnfreezeBuilder<'_, P, S> {
         builder::WithdrawUnfreeze::with_client(self)
     }
+    pub fn vote_witness(&self) -> builder::VoteWitnessBuilder<'_, P, S> {
+        builder::VoteWitness::with_client(self)
+    }
+    pub fn withdraw_rewards(
+        &self,
+    ) -> builder::WithdrawRewardsBuilder<'_, P, S> {
+        builder::WithdrawRewards::with_client(self)
+    }
+    pub fn witness_create(&self) -> builder::WitnessCreateBuilder<'_, P, S> {
+        builder::WitnessCreate::with_client(self)
+    }
+    pub fn witness_update(&self) -> builder::WitnessUpdateBuilder<'_, P, S> {
+        builder::WitnessUpdate::with_client(self)
+    }
+    pub fn update_brokerage(
+        &self,
+    ) -> builder::UpdateBrokerageBuilder<'_, P, S> {
+        builder::UpdateBrokerage::with_client(self)
+    }
+    pub fn exchange_create(&self) -> builder::ExchangeCreateBuilder<'_, P, S> {
+        builder::ExchangeCreate::with_client(self)
+    }
+    pub fn exchange_inject(&self) -> builder::ExchangeInjectBuilder<'_, P, S> {
+        builder::ExchangeInject::with_client(self)
+    }
+    pub fn exchange_withdraw(
+        &self,
+    ) -> builder::ExchangeWithdrawBuilder<'_, P, S> {
+        builder::ExchangeWithdraw::with_client(self)
+    }
+    /// Sell a token into a Bancor exchange
+    pub fn exchange_transaction(
+        &self,
+    ) -> builder::ExchangeTransactionBuilder<'_, P, S> {
+        builder::ExchangeTransaction::with_client(self)
+    }
+    /// Place a sell order on the on-chain DEX
+    pub fn market_sell(&self) -> builder::MarketSellBuilder<'_, P, S> {
+        builder::MarketSell::with_client(self)
+    }
+    pub fn market_cancel(&self) -> builder::MarketCancelBuilder<'_, P, S> {
+        builder::MarketCancel::with_client(self)
+    }
+    pub fn proposal_create(&self) -> builder::ProposalCreateBuilder<'_, P, S> {
+        builder::ProposalCreate::with_client(self)
+    }
+    pub fn proposal_approve(
+        &self,
+    ) -> builder::ProposalApproveBuilder<'_, P, S> {
+        builder::ProposalApprove::with_client(self)
+    }
+    pub fn proposal_delete(&self) -> builder::ProposalDeleteBuilder<'_, P, S> {
+        builder::ProposalDelete::with_client(self)
+    }
     pub fn create_contract(
         &self,
         contract: String,
@@ -101,6 +238,39 @@ where
         );
         listener.run().await
     }
+    /// Listener pairing every transaction with its receipt
+    pub async fn receipt_listener(
+        &self,
+        block_poll_interval: Duration,
+    ) -> ListenerHandle
+    where
+        P: Clone + Send + Sync + 'static,
+        S: Clone + Send + Sync + 'static,
+        S::Error: std::fmt::Debug,
+    {
+        let listener = crate::listener::Listener::new(
+            self.to_owned(),
+            block_poll_interval,
+        );
+        listener.with_receipts().run().await
+    }
+    /// Listener delivering only blocks that reached `confirmation`
+    pub async fn confirmed_listener(
+        &self,
+        block_poll_interval: Duration,
+        confirmation: Confirmation,
+    ) -> ListenerHandle
+    where
+        P: Clone + Send + Sync + 'static,
+        S: Clone + Send + Sync + 'static,
+        S::Error: std::fmt::Debug,
+    {
+        let listener = crate::listener::Listener::new(
+            self.to_owned(),
+            block_poll_interval,
+        );
+        listener.with_confirmation(confirmation).run().await
+    }
     pub async fn account_permissions(
         &self,
         address: TronAddress,
@@ -111,18 +281,221 @@ where
         PermissionHandler::new(self, address).await
     }
     pub async fn energy_price(&self) -> Result<Trx> {
-        let chain_parameters = self.provider.chain_parameters().await?;
-        let energy_price = chain_parameters
-            .get("getEnergyFee")
-            .ok_or(eyre!("not found getTransactionFee"))?;
-        Ok((*energy_price).into())
+        Ok(self.provider.chain_parameters().await?.energy_fee)
     }
     pub async fn bandwidth_price(&self) -> Result<Trx> {
+        Ok(self.provider.chain_parameters().await?.transaction_fee)
+    }
+    /// Energy price effective at `time`
+    pub async fn energy_price_at(&self, time: OffsetDateTime) -> Result<Trx> {
+        self.provider
+            .get_energy_prices()
+            .await?
+            .price_at(time)
+            .ok_or_else(|| Error::NotFound(format!("energy price at {time}")))
+    }
+    /// Bandwidth price effective at `time`
+    pub async fn bandwidth_price_at(
+        &self,
+        time: OffsetDateTime,
+    ) -> Result<Trx> {
+        self.provider
+            .get_bandwidth_prices()
+            .await?
+            .price_at(time)
+            .ok_or_else(|| {
+                Error::NotFound(format!("bandwidth price at {time}"))
+            })
+    }
+    /// Fees of an included transaction, attributed with prices effective
+    /// at its block
+    pub async fn fee_attribution(
+        &self,
+        txid: Hash32,
+    ) -> Result<FeeAttribution> {
+        let (info, energy_prices, bandwidth_prices) = tokio::try_join!(
+            self.provider.get_transaction_info(txid),
+            self.provider.get_energy_prices(),
+            self.provider.get_bandwidth_prices()
+        )?;
+        let at = info.block_time_stamp;
+        let not_found = |resource: &str| {
+            Error::NotFound(format!("{resource} price at {at}"))
+        };
+        Ok(FeeAttribution::new(
+            &info,
+            energy_prices
+                .price_at(at)
+                .ok_or_else(|| not_found("energy"))?,
+            bandwidth_prices
+                .price_at(at)
+                .ok_or_else(|| not_found("bandwidth"))?,
+        ))
+    }
+    pub async fn fee_parameters(&self) -> Result<FeeParameters> {
         let chain_parameters = self.provider.chain_parameters().await?;
-        let bandwidth_unit_price = chain_parameters
-            .get("getTransactionFee")
-            .ok_or(eyre!("not found getTransactionFee"))?;
-        Ok((*bandwidth_unit_price).into())
+        Ok(FeeParameters::from_chain_parameters(&chain_parameters))
+    }
+    /// Projection of `address` bandwidth and energy regeneration
+    pub async fn resource_projection(
+        &self,
+        address: TronAddress,
+    ) -> Result<ResourceProjection> {
+        let (account, resources) = tokio::try_join!(
+            self.provider.get_account(address),
+            self.provider.get_account_resources(address)
+        )?;
+        Ok(ResourceProjection::new(&account, &resources))
+    }
+    /// Stake planner with current network totals, `address` can be any
+    /// existing account.
+    pub async fn stake_planner(
+        &self,
+        address: TronAddress,
+    ) -> Result<stake::StakePlanner> {
+        let resources = self.provider.get_account_resources(address).await?;
+        Ok(stake::StakePlanner::new(&resources))
+    }
+    /// Witnesses ranked by expected voter APR, with real-time votes and
+    /// current brokerage. Use [`WitnessRanking::propose_votes`] to split
+    /// tron power among them.
+    pub async fn witness_ranking(&self) -> Result<WitnessRanking> {
+        let limit = WitnessRewardParams::REWARDED_WITNESSES as i64;
+        let (witnesses, chain_parameters) = tokio::try_join!(
+            self.provider.get_paginated_now_witness_list(0, limit),
+            self.provider.chain_parameters()
+        )?;
+        let params =
+            WitnessRewardParams::from_chain_parameters(&chain_parameters);
+        let brokerage = futures::future::try_join_all(
+            witnesses
+                .iter()
+                .map(|w| self.provider.get_brokerage_info(w.address)),
+        )
+        .await?;
+        let brokerage =
+            witnesses.iter().map(|w| w.address).zip(brokerage).collect();
+        Ok(WitnessRanking::new(witnesses, &brokerage, params))
+    }
+    /// TRC-10 balance of `address` in the smallest asset units
+    pub async fn asset_balance(
+        &self,
+        address: TronAddress,
+        asset_id: &str,
+    ) -> Result<i64> {
+        let account = self.provider.get_account(address).await?;
+        Ok(account.asset_v2.get(asset_id).copied().unwrap_or_default())
+    }
+    /// Dry run of a contract call with the real owner and call value.
+    /// Revert data is decoded, custom errors with the contract ABI.
+    pub async fn simulate(
+        &self,
+        trigger: TriggerSmartContract,
+    ) -> Result<Simulation> {
+        let contract_address = trigger.contract_address;
+        let txext = self.provider.trigger_constant_contract(trigger).await?;
+        let failed = txext
+            .transaction
+            .as_ref()
+            .and_then(|t| t.result.first())
+            .is_some_and(|r| r.ret == TxCode::Failed);
+        let return_data =
+            txext.constant_result.into_iter().next().unwrap_or_default();
+
+        let revert = if failed {
+            let message = txext.result.map(|r| r.message).unwrap_or_default();
+            let mut revert = Revert::decode(&return_data, &message, None);
+            // Without the ABI the error stays unnamed, the revert is known
+            if let Revert::Custom { name: None, .. } = revert {
+                let abi = self
+                    .provider
+                    .get_contract_info(contract_address)
+                    .await
+                    .inspect_err(|e| {
+                        tracing::warn!(?e, "failed to get contract abi")
+                    })
+                    .ok()
+                    .and_then(|info| info.smart_contract)
+                    .map(|c| c.abi);
+                revert = Revert::decode(&return_data, &message, abi.as_ref());
+            }
+            Some(revert)
+        } else {
+            None
+        };
+        Ok(Simulation {
+            return_data,
+            logs: txext.logs,
+            energy_used: txext.energy_used,
+            energy_penalty: txext.energy_penalty,
+            revert,
+        })
+    }
+    pub async fn exchanges(&self) -> Result<Vec<Exchange>> {
+        self.provider.list_exchanges().await
+    }
+    /// Expected result of selling `quant` of `token_id` into an exchange
+    pub async fn exchange_quote(
+        &self,
+        exchange_id: i64,
+        token_id: &str,
+        quant: i64,
+    ) -> Result<ExchangeQuote> {
+        self.provider
+            .get_exchange_by_id(exchange_id)
+            .await?
+            .quote(token_id, quant)
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "token {token_id} is not traded on exchange {exchange_id}"
+                ))
+            })
+    }
+    /// Exchanged amounts of an included exchange transaction
+    pub async fn exchange_outcome(
+        &self,
+        txid: Hash32,
+    ) -> Result<ExchangeOutcome> {
+        let info = self.provider.get_transaction_info(txid).await?;
+        Ok((&info).into())
+    }
+    pub async fn market_orders(
+        &self,
+        address: TronAddress,
+    ) -> Result<Vec<MarketOrder>> {
+        self.provider.get_market_order_by_account(address).await
+    }
+    /// Fills at placement of an order sent with
+    /// [`market_sell`](Self::market_sell)
+    pub async fn market_fill(&self, txid: Hash32) -> Result<MarketFill> {
+        let info = self.provider.get_transaction_info(txid).await?;
+        MarketFill::from_info(&info).ok_or_else(|| {
+            Error::NotFound(format!("no market order in {txid:?}"))
+        })
+    }
+    /// Current state of an order sent with
+    /// [`market_sell`](Self::market_sell), including later fills
+    pub async fn market_order_by_txid(
+        &self,
+        txid: Hash32,
+    ) -> Result<MarketOrder> {
+        let fill = self.market_fill(txid).await?;
+        self.provider.get_market_order_by_id(fill.order_id).await
+    }
+    pub async fn proposals(&self) -> Result<Vec<Proposal>> {
+        self.provider.list_proposals().await
+    }
+    /// Proposal with its changes against current chain parameters
+    pub async fn proposal_diff(
+        &self,
+        proposal_id: i64,
+    ) -> Result<(Proposal, Vec<ProposalChange>)> {
+        let (proposal, chain_parameters) = tokio::try_join!(
+            self.provider.get_proposal_by_id(proposal_id),
+            self.provider.chain_parameters()
+        )?;
+        let diff = proposal.diff(&chain_parameters);
+        Ok((proposal, diff))
     }
     pub async fn check_account(
         &self,
@@ -132,3 +505,30 @@ where
         Ok(account.status())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::provider::mock::MockProvider;
+    use crate::signer::LocalSigner;
+
+    #[tokio::test]
+    async fn signer_is_not_replaced_under_jit_energy() {
+        let client = Client::builder()
+            .provider(MockProvider::new().await)
+            .signer(LocalSigner::rand())
+            .build();
+        let client = client.with_signer(LocalSigner::rand());
+        assert!(client.is_ok());
+
+        let jit = jit_energy::JitEnergy::builder()
+            .pool(LocalSigner::rand().address())
+            .signer(LocalSigner::rand())
+            .build();
+        let client = client.map(|c| c.with_jit_energy(jit));
+        assert!(matches!(
+            client.and_then(|c| c.with_signer(LocalSigner::rand())),
+            Err(Error::PreconditionFailed(_))
+        ));
+    }
+}
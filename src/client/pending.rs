@@ -9,11 +9,17 @@ use prost::Message;
 use time::OffsetDateTime;
 use time::ext::NumericalDuration;
 
+use crate::domain;
 use crate::domain::account::AccountResourceUsage;
 use crate::domain::account::{Account, AccountStatus};
 use crate::domain::address::TronAddress;
-use crate::domain::contract::TriggerSmartContract;
-use crate::domain::estimate::{MissingResource, Resource, ResourceState};
+use crate::domain::contract::{
+    ContractType, ResourceCode, TriggerSmartContract,
+};
+use crate::domain::estimate::{
+    EnergyEstimate, EnergyEstimateMethod, FeeBreakdown, FeeParameters,
+    MissingResource, Resource, ResourceState,
+};
 use crate::domain::permission::Permission;
 use crate::domain::transaction::{Transaction, TransactionInfo, TxCode};
 use crate::domain::trx::Trx;
@@ -25,13 +31,17 @@ use crate::provider::TronProvider;
 use crate::signer::PrehashSigner;
 use crate::utility::generate_txid;
 use crate::{Result, protocol, utility};
-use crate::{domain, trx};
 
 use super::Client;
+use super::fee_limit::FeeLimitContext;
+use super::simulate::Simulation;
 
 pub struct AutoSigning;
 pub struct ManualSigning;
 
+/// Permission of witness accounts, used to sign blocks
+const WITNESS_PERMISSION_ID: i32 = 1;
+
 #[derive(Clone, Copy, Debug)]
 pub struct ActivationFeeCheck {
     pub address: TronAddress,
@@ -67,7 +77,7 @@ pub struct PendingTransaction<'a, P, S, M = AutoSigning> {
     pub(super) can_spend_trx_for_fee: bool,
 
     /// Cache energy in this PendingTransaction lifecycle
-    pub(super) cached_energy: Arc<Mutex<Option<i64>>>,
+    pub(super) cached_energy: Arc<Mutex<Option<EnergyEstimate>>>,
 }
 
 impl<'a, P, S, M> PendingTransaction<'a, P, S, M>
@@ -147,32 +157,23 @@ where
             ins.suggested_trx_topup.iter().map(|(_, t)| *t).sum();
         suggested <= state.remaining.trx
     }
-    fn fee_limit_fallback(&self) -> Trx {
-        if let Some(c) = self.transaction.raw.contract.first() {
-            match c.contract_type {
-                domain::contract::ContractType::CreateSmartContract(_) => {
-                    trx!(1000.0 TRX)
-                }
-                domain::contract::ContractType::TriggerSmartContract(_) => {
-                    trx!(200.0 TRX)
-                }
-                _ => trx!(50.0 TRX),
-            }
-        } else {
-            trx!(50.0 TRX)
-        }
-    }
     async fn update_fee_limit(&mut self) -> Result<()> {
-        let fee_limit_trx = match self.estimate_energy_cached().await {
-            Ok(Some(energy)) => {
-                let energy_price = self.client.energy_price().await?;
-                let sun =
-                    (((energy as f64) * 1.5) as i64) * energy_price.to_sun();
-                Trx::from_sun(sun)
-            }
-            Ok(None) => self.fee_limit_fallback(),
-            Err(e) => return Err(e),
+        let (estimate, energy_price) = tokio::join!(
+            self.estimate_energy_cached(),
+            self.client.energy_price()
+        );
+        let estimate = estimate?;
+        // Strategies fall back to fixed caps without a price
+        let energy_price = energy_price
+            .inspect_err(|e| tracing::warn!(?e, "failed to get energy price"))
+            .ok();
+        let ctx = FeeLimitContext {
+            contract: self.transaction.raw.contract.first().cloned(),
+            estimated_energy: estimate.map(|e| e.energy),
+            estimate_method: estimate.map(|e| e.method),
+            energy_price,
         };
+        let fee_limit_trx = self.client.fee_limit_strategy.fee_limit(&ctx);
 
         self.transaction.raw.fee_limit = fee_limit_trx.to_sun().into();
         Ok(())
@@ -197,12 +198,21 @@ where
         &self,
         permission: &Permission,
     ) -> Result<i64> {
-        let raw = self.transaction.raw.clone();
-        let signature_count = permission
+        let raw =
+            protocol::transaction::Raw::from(self.transaction.raw.clone());
+        let signature_count = Self::signature_count(permission)?;
+        Ok(utility::transaction_bandwidth(
+            raw.encoded_len(),
+            signature_count as usize,
+            raw.contract.len(),
+        ))
+    }
+    /// Signatures required by `permission`, at least one
+    fn signature_count(permission: &Permission) -> Result<i64> {
+        Ok(permission
             .required_signatures()
-            .context("insufficient keys for threshold")?;
-        let txlen = protocol::transaction::Raw::from(raw).encode_to_vec().len();
-        Ok(utility::estimate_bandwidth(txlen as i64, signature_count))
+            .context("insufficient keys for threshold")?
+            .max(1))
     }
     async fn activation_fee(&self) -> Result<Trx> {
         let mut total = Trx::ZERO;
@@ -214,10 +224,100 @@ where
         }
         Ok(total)
     }
-    async fn required_trx(&self) -> Result<Trx> {
-        Ok(self.base_trx_required + self.activation_fee().await?)
+    /// Multisig and memo fees
+    fn surcharges(
+        &self,
+        permission: &Permission,
+        params: &FeeParameters,
+    ) -> Result<(Trx, Trx)> {
+        let multi_sign_fee = if Self::signature_count(permission)? > 1 {
+            params.multi_sign_fee
+        } else {
+            Trx::ZERO
+        };
+        let memo_fee = if self.transaction.raw.data.is_empty() {
+            Trx::ZERO
+        } else {
+            params.memo_fee
+        };
+        Ok((multi_sign_fee, memo_fee))
+    }
+    async fn required_trx(&self, permission: &Permission) -> Result<Trx> {
+        let (activation_fee, params) = tokio::try_join!(
+            self.activation_fee(),
+            self.client.fee_parameters()
+        )?;
+        let (multi_sign_fee, memo_fee) =
+            self.surcharges(permission, &params)?;
+        Ok(self.base_trx_required + activation_fee + multi_sign_fee + memo_fee)
+    }
+    fn permission(&self, account: &Account) -> Result<Permission> {
+        let permission_id = self
+            .transaction
+            .raw
+            .contract
+            .first()
+            .context("no contract found")?
+            .permission_id;
+        Ok(account
+            .permission_by_id(permission_id)
+            .context("no permission found")?)
     }
-    async fn estimate_energy_cached(&self) -> Result<Option<i64>> {
+    /// Itemized fees this transaction will burn, based on current chain
+    /// parameters and owner resources.
+    pub async fn fee_breakdown(&self) -> Result<FeeBreakdown> {
+        let account = self.client.provider.get_account(self.owner).await?;
+        let permission = self.permission(&account)?;
+        let bandwidth = self.estimate_bandwidth_with_permission(&permission)?;
+
+        let (resources, energy, activation_fee, params) = tokio::try_join!(
+            self.client.provider.get_account_resources(self.owner),
+            self.estimate_energy_cached(),
+            self.activation_fee(),
+            self.client.fee_parameters()
+        )?;
+        let (multi_sign_fee, memo_fee) =
+            self.surcharges(&permission, &params)?;
+
+        // Transfers to a new account burn `create_account_fee` as
+        // bandwidth, unless staked bandwidth covers the creation
+        let creates_account = activation_fee > Trx::ZERO
+            && self.transaction.raw.contract.first().is_some_and(|c| {
+                matches!(
+                    c.contract_type,
+                    ContractType::TransferContract(_)
+                        | ContractType::TransferAssetContract(_)
+                )
+            });
+        let bandwidth_fee = params.bandwidth_fee(
+            bandwidth,
+            resources.free_net_limit - resources.free_net_used,
+            resources.net_limit - resources.net_used,
+            creates_account,
+        );
+        let activation_fee = if creates_account {
+            activation_fee - params.create_account_fee
+        } else {
+            activation_fee
+        };
+
+        let energy = energy.map(|e| e.energy).unwrap_or_default();
+        let available_energy =
+            (resources.energy_limit - resources.energy_used).max(0);
+        let energy_fee = params.energy_price
+            * energy.saturating_sub(available_energy).max(0);
+
+        Ok(FeeBreakdown {
+            bandwidth,
+            bandwidth_fee,
+            energy,
+            energy_fee,
+            activation_fee,
+            multi_sign_fee,
+            memo_fee,
+        })
+    }
+    async fn estimate_energy_cached(&self) -> Result<Option<EnergyEstimate>> {
         {
             let guard = self
                 .cached_energy
@@ -241,30 +341,20 @@ where
         &self,
         account: &Account,
     ) -> Result<ResourceState> {
-        let permission_id = self
-            .transaction
-            .raw
-            .contract
-            .first()
-            .context("no contract found")?
-            .permission_id;
-
-        let permission = account
-            .permission_by_id(permission_id)
-            .context("no permission found")?;
+        let permission = self.permission(account)?;
 
         let bandwidth = self.estimate_bandwidth_with_permission(&permission)?;
 
         let (resources, energy, required_trx, energy_price) = tokio::try_join!(
             self.client.provider.get_account_resources(self.owner),
             self.estimate_energy_cached(),
-            self.required_trx(),
+            self.required_trx(&permission),
             self.client.energy_price()
         )?;
 
         let required = Resource {
             bandwidth,
-            energy: energy.unwrap_or_else(|| {
+            energy: energy.map(|e| e.energy).unwrap_or_else(|| {
                 let fee_limit_sun = self.transaction.raw.fee_limit.to_sun();
                 let price_sun = energy_price.to_sun().max(1);
                 (fee_limit_sun + price_sun - 1) / price_sun
@@ -304,45 +394,92 @@ where
 
         self.estimate_bandwidth_with_permission(&permission)
     }
-    pub async fn estimate_energy(&self) -> Option<i64> {
-        let safe_call = |c: TriggerSmartContract| async move {
-            match self.client.provider.trigger_constant_contract(c).await {
-                Ok(txext) => Some(txext.energy_used),
-                Err(e) => {
-                    tracing::warn!(
-                        ?e,
-                        "energy estimation failed, fallback to fee_limit"
-                    );
-                    None
-                }
+    /// Estimate energy with the `EstimateEnergy` RPC, falling back to a
+    /// constant call if the node doesn't support it.
+    pub async fn estimate_energy(&self) -> Option<EnergyEstimate> {
+        let contract = self.transaction.raw.contract.first()?;
+        match contract.contract_type {
+            domain::contract::ContractType::TriggerSmartContract(ref c) => {
+                self.estimate_contract_energy(c.clone()).await
+            }
+            domain::contract::ContractType::CreateSmartContract(
+                ref contract,
+            ) => {
+                let bytecode = contract.new_contract.bytecode.clone();
+                self.estimate_contract_energy(TriggerSmartContract {
+                    owner_address: contract.owner_address,
+                    data: bytecode.into(),
+                    call_token_value: contract.call_token_value,
+                    ..Default::default()
+                })
+                .await
+            }
+            domain::contract::ContractType::TransferContract(_) => {
+                Some(EnergyEstimate {
+                    energy: 0,
+                    method: EnergyEstimateMethod::NotRequired,
+                })
+            }
+            _ => None,
+        }
+    }
+    async fn estimate_contract_energy(
+        &self,
+        contract: TriggerSmartContract,
+    ) -> Option<EnergyEstimate> {
+        let provider = &self.client.provider;
+        match provider.estimate_energy(contract.clone()).await {
+            Ok(energy) => {
+                return Some(EnergyEstimate {
+                    energy,
+                    method: EnergyEstimateMethod::EstimateEnergy,
+                });
+            }
+            Err(e) => {
+                tracing::debug!(
+                    ?e,
+                    "estimate_energy failed, try constant call"
+                );
+            }
+        }
+
+        let contract_address = contract.contract_address;
+        let txext = match provider.trigger_constant_contract(contract).await {
+            Ok(txext) => txext,
+            Err(e) => {
+                tracing::warn!(
+                    ?e,
+                    "energy estimation failed, fallback to fee_limit"
+                );
+                return None;
             }
         };
-        if let Some(contract) = self.transaction.raw.contract.first() {
-            match contract.contract_type {
-                domain::contract::ContractType::TriggerSmartContract(ref c) => {
-                    let energy = safe_call(c.clone()).await;
-                    return energy;
-                }
-                domain::contract::ContractType::CreateSmartContract(
-                    ref contract,
-                ) => {
-                    let bytecode = contract.new_contract.bytecode.clone();
-                    let energy = safe_call(TriggerSmartContract {
-                        owner_address: contract.owner_address,
-                        data: bytecode.into(),
-                        call_token_value: contract.call_token_value,
-                        ..Default::default()
+        let constant_call = EnergyEstimate {
+            energy: txext.energy_used,
+            method: EnergyEstimateMethod::ConstantCall,
+        };
+
+        // `energy_used` already includes `energy_penalty`, the factor
+        // must be applied only if the node didn't account for it.
+        if txext.energy_penalty > 0 || contract_address == TronAddress::ZERO {
+            return Some(constant_call);
+        }
+        match provider.get_contract_info(contract_address).await {
+            Ok(info) => match info.contract_state {
+                Some(state) if state.energy_factor > 0 => {
+                    Some(EnergyEstimate {
+                        energy: state.apply_energy_factor(txext.energy_used),
+                        method:
+                            EnergyEstimateMethod::ConstantCallWithEnergyFactor,
                     })
-                    .await;
-                    return energy;
                 }
-                domain::contract::ContractType::TransferContract(_) => {
-                    return Some(0);
-                }
-                _ => (),
+                _ => Some(constant_call),
+            },
+            Err(e) => {
+                tracing::warn!(?e, "failed to get contract energy factor");
+                Some(constant_call)
             }
         }
-        None
     }
     pub async fn estimate_transaction(&self) -> Result<ResourceState> {
         let account = self.client.provider.get_account(self.owner).await?;
@@ -394,6 +531,24 @@ where
     pub fn transaction(&self) -> Transaction {
         self.transaction.clone()
     }
+    /// Dry run of a contract call before broadcasting, see
+    /// [`Client::simulate`]
+    pub async fn simulate(&self) -> Result<Simulation> {
+        match self
+            .transaction
+            .raw
+            .contract
+            .first()
+            .map(|c| &c.contract_type)
+        {
+            Some(domain::contract::ContractType::TriggerSmartContract(c)) => {
+                self.client.simulate(c.clone()).await
+            }
+            _ => Err(Error::InvalidInput(
+                "only contract calls can be simulated".into(),
+            )),
+        }
+    }
     /// Expiration is limited to 24 hours
     pub async fn set_expiration(
         mut self,
@@ -418,7 +573,152 @@ where
     S: PrehashSigner + Clone,
     error::Error: From<S::Error>,
 {
-    pub async fn broadcast(mut self, ctx: &S::Ctx) -> Result<Hash32> {
+    /// Delegates missing energy from the JIT pool, if configured.
+    /// Returns delegated amount, nothing stays delegated on error.
+    async fn jit_delegate(&self, ctx: &S::Ctx) -> Result<Option<Trx>> {
+        let Some(jit) = self.client.jit_energy.as_ref() else {
+            return Ok(None);
+        };
+        let Some(contract) = self.transaction.raw.contract.first() else {
+            return Ok(None);
+        };
+        if !matches!(
+            contract.contract_type,
+            domain::contract::ContractType::TriggerSmartContract(_)
+        ) || self.owner == jit.pool
+        {
+            return Ok(None);
+        }
+
+        let (estimate, state) = tokio::try_join!(
+            self.estimate_energy_cached(),
+            self.estimate_transaction()
+        )?;
+        let Some((available, required)) = state
+            .insufficient
+            .iter()
+            .flat_map(|i| &i.missing)
+            .find_map(|m| match m {
+                MissingResource::Energy {
+                    available,
+                    required,
+                } => Some((*available, *required)),
+                _ => None,
+            })
+        else {
+            return Ok(None);
+        };
+
+        let planner = self.client.stake_planner(jit.pool).await?;
+        let Some(amount) = jit.delegation_amount(
+            estimate,
+            required - available.max(0),
+            &planner,
+        ) else {
+            tracing::warn!(
+                owner = %self.owner,
+                ?estimate,
+                "jit energy skipped, energy unknown or above max delegation"
+            );
+            return Ok(None);
+        };
+
+        let mut delegation = self
+            .client
+            .delegate()
+            .owner(jit.pool)
+            .receiver(self.owner)
+            .resource(ResourceCode::Energy)
+            .amount(amount)
+            .build::<ManualSigning>()
+            .await?;
+        delegation.sign(&jit.signer, ctx).await?;
+        delegation.broadcast().await?;
+        tracing::info!(owner = %self.owner, %amount, "jit energy delegated");
+
+        // Wait until delegated energy is visible for the owner
+        let deadline = tokio::time::Instant::now() + jit.timeout;
+        let landed = async {
+            loop {
+                let resources = self
+                    .client
+                    .provider
+                    .get_account_resources(self.owner)
+                    .await?;
+                if resources.energy_limit - resources.energy_used >= required {
+                    return Ok(());
+                }
+                if tokio::time::Instant::now() >= deadline {
+                    return Err(Error::PreconditionFailed(
+                        "jit energy delegation didn't land in time".into(),
+                    ));
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        };
+        if let Err(e) = landed.await {
+            Self::jit_return(self.client, self.owner, amount, ctx).await;
+            return Err(e);
+        }
+
+        Ok(Some(amount))
+    }
+    /// Returns delegated energy to the JIT pool, failures are only logged
+    async fn jit_return(
+        client: &Client<P, S>,
+        owner: TronAddress,
+        amount: Trx,
+        ctx: &S::Ctx,
+    ) {
+        let Some(jit) = client.jit_energy.as_ref() else {
+            return;
+        };
+        let undelegated = async {
+            let mut undelegation = client
+                .undelegate()
+                .owner(jit.pool)
+                .receiver(owner)
+                .resource(ResourceCode::Energy)
+                .amount(amount)
+                .build::<ManualSigning>()
+                .await?;
+            undelegation.sign(&jit.signer, ctx).await?;
+            undelegation.broadcast().await
+        };
+        match undelegated.await {
+            Ok(_) => tracing::info!(%owner, %amount, "jit energy undelegated"),
+            Err(e) => {
+                tracing::warn!(?e, %owner, %amount, "jit undelegation failed")
+            }
+        }
+    }
+    /// Waits for `txid` to be included, at most `JitEnergy::timeout`, and
+    /// returns delegated energy to the JIT pool
+    async fn jit_undelegate(
+        client: &Client<P, S>,
+        owner: TronAddress,
+        txid: Hash32,
+        amount: Trx,
+        ctx: &S::Ctx,
+    ) {
+        let Some(jit) = client.jit_energy.as_ref() else {
+            return;
+        };
+        let deadline = tokio::time::Instant::now() + jit.timeout;
+        loop {
+            match client.provider.get_transaction_info(txid).await {
+                Ok(info) if info.block_number > 0 => break,
+                // Not included in time, it will burn TRX if it ever lands
+                _ if tokio::time::Instant::now() >= deadline => {
+                    tracing::warn!(?txid, "transaction not included in time");
+                    break;
+                }
+                _ => tokio::time::sleep(Duration::from_secs(3)).await,
+            }
+        }
+        Self::jit_return(client, owner, amount, ctx).await;
+    }
+    async fn sign_and_broadcast(mut self, ctx: &S::Ctx) -> Result<Hash32> {
         self.validate_unsigned().await?;
 
         let signer =
@@ -442,6 +742,35 @@ where
 
         Ok(self.txid)
     }
+    /// With [`JitEnergy`] configured, missing energy is delegated first.
+    /// If [`JitEnergy::undelegate`] is set, this waits for the transaction
+    /// to be included (up to [`JitEnergy::timeout`]) before returning.
+    ///
+    /// [`JitEnergy`]: super::jit_energy::JitEnergy
+    /// [`JitEnergy::undelegate`]: super::jit_energy::JitEnergy::undelegate
+    /// [`JitEnergy::timeout`]: super::jit_energy::JitEnergy::timeout
+    pub async fn broadcast(self, ctx: &S::Ctx) -> Result<Hash32> {
+        let (client, owner) = (self.client, self.owner);
+        let jit_delegated = self.jit_delegate(ctx).await?;
+
+        let txid = match self.sign_and_broadcast(ctx).await {
+            Ok(txid) => txid,
+            Err(e) => {
+                if let Some(amount) = jit_delegated {
+                    Self::jit_return(client, owner, amount, ctx).await;
+                }
+                return Err(e);
+            }
+        };
+
+        if let Some(amount) = jit_delegated
+            && client.jit_energy.as_ref().is_some_and(|jit| jit.undelegate)
+        {
+            Self::jit_undelegate(client, owner, txid, amount, ctx).await;
+        }
+
+        Ok(txid)
+    }
     /// Wait for confirmations and get transaction info
     pub async fn broadcast_get_receipt(
         self,
@@ -465,14 +794,20 @@ where
     S: PrehashSigner + Clone,
     error::Error: From<S::Error>,
 {
+    /// Multisig fee is charged (see [`PendingTransaction::fee_breakdown`])
+    /// when the permission requires more than one signature.
+    ///
+    /// Id 1 is `Account::witness_permission`, see
+    /// [`PendingTransaction::set_witness_permission`].
     pub async fn set_permission(&mut self, id: i32) -> Result<()> {
-        let permission = self
-            .client
-            .provider
-            .get_account(self.owner)
-            .await?
-            .permission_by_id(id)
-            .ok_or(Error::NotFound("no permission with id found".into()))?;
+        let account = self.client.provider.get_account(self.owner).await?;
+        if account.permission_by_id(id).is_none() {
+            return Err(Error::NotFound(if id == WITNESS_PERMISSION_ID {
+                "account has no witness permission".into()
+            } else {
+                "no permission with id found".into()
+            }));
+        }
         self.transaction
             .raw
             .contract
@@ -480,14 +815,13 @@ where
             .context("no contract part found")?
             .permission_id = id;
         self.refresh_txid().await?;
-
-        if permission.keys.len() > 1 {
-            // Multisig fee
-            self.base_trx_required += trx!(1.0 TRX);
-        }
-
         Ok(())
     }
+    /// Sign with the keys of the witness permission, which a witness
+    /// account may set apart from its owner keys
+    pub async fn set_witness_permission(&mut self) -> Result<()> {
+        self.set_permission(WITNESS_PERMISSION_ID).await
+    }
     pub async fn sign(
         &mut self,
         signer: &S,
@@ -790,6 +1124,7 @@ where
 
                                 // Return when we reach the required confirmations
                                 if confirmation_count >= confirmations {
+                                    client.fee_limit_strategy.observe(&tx_info);
                                     return Ok(tx_info);
                                 }
                             }
@@ -817,3 +1152,130 @@ where
 
     Err(Error::TransactionTimeout.into())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::contract::{ContractState, SmartContractDataWrapper};
+    use crate::domain::transaction::TransactionExtention;
+    use crate::provider::mock::MockProvider;
+    use crate::signer::LocalSigner;
+
+    fn address(n: u8) -> TronAddress {
+        let mut bytes = [n; 21];
+        bytes[0] = 0x41;
+        TronAddress::try_from(bytes.as_slice()).unwrap_or_default()
+    }
+
+    fn constant_call(
+        energy_used: i64,
+        energy_penalty: i64,
+    ) -> TransactionExtention {
+        TransactionExtention {
+            transaction: None,
+            txid: Default::default(),
+            constant_result: Vec::new(),
+            energy_used,
+            energy_penalty,
+            result: None,
+            logs: Vec::new(),
+            internal_transactions: Vec::new(),
+            info: None,
+        }
+    }
+
+    async fn estimate(provider: MockProvider) -> Option<EnergyEstimate> {
+        let client = Client::<_, LocalSigner>::builder()
+            .provider(provider)
+            .build();
+        let pending = PendingTransaction::<_, _, AutoSigning> {
+            client: &client,
+            transaction: Transaction::default(),
+            txid: Default::default(),
+            _mode: PhantomData,
+            owner: address(1),
+            base_trx_required: Trx::ZERO,
+            activation_checks: Vec::new(),
+            can_spend_trx_for_fee: true,
+            cached_energy: Arc::new(Mutex::new(None)),
+        };
+        pending
+            .estimate_contract_energy(TriggerSmartContract {
+                owner_address: address(1),
+                contract_address: address(2),
+                ..Default::default()
+            })
+            .await
+    }
+
+    fn with_energy_factor(provider: &MockProvider, energy_factor: i64) {
+        provider.update(|s| {
+            s.contracts.insert(
+                address(2),
+                SmartContractDataWrapper {
+                    contract_state: Some(ContractState {
+                        energy_factor,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            );
+        });
+    }
+
+    #[tokio::test]
+    async fn energy_estimate_fallback_order() {
+        let provider = MockProvider::new().await;
+        assert!(estimate(provider.clone()).await.is_none());
+
+        with_energy_factor(&provider, 5_000);
+        provider.update(|s| s.constant_call = Some(constant_call(1_000, 0)));
+        let energy = estimate(provider.clone()).await;
+        assert_eq!(energy.map(|e| e.energy), Some(1_500));
+        assert_eq!(
+            energy.map(|e| e.method),
+            Some(EnergyEstimateMethod::ConstantCallWithEnergyFactor)
+        );
+
+        provider.update(|s| s.estimate_energy = Some(2_000));
+        let energy = estimate(provider.clone()).await;
+        assert_eq!(energy.map(|e| e.energy), Some(2_000));
+        assert_eq!(
+            energy.map(|e| e.method),
+            Some(EnergyEstimateMethod::EstimateEnergy)
+        );
+    }
+
+    #[tokio::test]
+    async fn constant_call_without_energy_factor() {
+        let provider = MockProvider::new().await;
+        provider.update(|s| s.constant_call = Some(constant_call(1_000, 0)));
+        let energy = estimate(provider.clone()).await;
+        assert_eq!(energy.map(|e| e.energy), Some(1_000));
+        assert_eq!(
+            energy.map(|e| e.method),
+            Some(EnergyEstimateMethod::ConstantCall)
+        );
+
+        with_energy_factor(&provider, 0);
+        let energy = estimate(provider).await;
+        assert_eq!(energy.map(|e| e.energy), Some(1_000));
+        assert_eq!(
+            energy.map(|e| e.method),
+            Some(EnergyEstimateMethod::ConstantCall)
+        );
+    }
+
+    #[tokio::test]
+    async fn energy_penalty_is_not_applied_twice() {
+        let provider = MockProvider::new().await;
+        with_energy_factor(&provider, 5_000);
+        provider.update(|s| s.constant_call = Some(constant_call(1_500, 500)));
+        let energy = estimate(provider).await;
+        assert_eq!(energy.map(|e| e.energy), Some(1_500));
+        assert_eq!(
+            energy.map(|e| e.method),
+            Some(EnergyEstimateMethod::ConstantCall)
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use alloy_sol_types::{SolCall, SolEvent};
+
+use crate::Result;
+use crate::contracts::revert::Revert;
+use crate::domain::transaction::{DecodedLog, Log, decode_logs};
+use crate::error::Error;
+
+/// Dry run of a `TriggerSmartContract` through `TriggerConstantContract`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+    /// Return data, or revert data if the call failed
+    pub return_data: Vec<u8>,
+    /// Logs the call would emit, empty if it failed
+    pub logs: Vec<Log>,
+    /// Includes `energy_penalty`
+    pub energy_used: i64,
+    pub energy_penalty: i64,
+    /// `None` if the call succeeded
+    pub revert: Option<Revert>,
+}
+
+impl Simulation {
+    pub fn success(&self) -> bool {
+        self.revert.is_none()
+    }
+    /// Fails with [`Error::Revert`] if the call failed
+    pub fn ensure_success(self) -> Result<Self> {
+        match self.revert {
+            Some(revert) => Err(Error::Revert(revert)),
+            None => Ok(self),
+        }
+    }
+    /// Events `E` the call would emit
+    pub fn decode_logs<E: SolEvent>(&self) -> Vec<DecodedLog<E>> {
+        decode_logs(&self.logs, None)
+    }
+    /// Decodes return data of a successful call
+    pub fn decode_return<C: SolCall>(&self) -> Result<C::Return> {
+        if let Some(revert) = &self.revert {
+            return Err(Error::Revert(revert.clone()));
+        }
+        C::abi_decode_returns(&self.return_data)
+            .map_err(|e| Error::Unexpected(e.into()))
+    }
+}
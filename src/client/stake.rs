@@ -0,0 +1,195 @@
+use crate::domain::account::AccountResourceUsage;
+use crate::domain::address::TronAddress;
+use crate::domain::contract::ResourceCode;
+use crate::domain::trx::Trx;
+use crate::provider::TronProvider;
+use crate::signer::PrehashSigner;
+
+use super::Client;
+use super::builder::{
+    DelegateBuilder, FreezeBalanceBuilder, delegate_builder,
+    freeze_balance_builder,
+};
+
+/// Only whole TRX count as staking weight
+const SUN_PER_TRX: i64 = 1_000_000;
+
+/// Converts between staked TRX and daily energy/bandwidth (Stake 2.0),
+/// using network-wide totals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StakePlanner {
+    /// `TotalEnergyCurrentLimit`
+    pub total_energy_limit: i64,
+    /// `TotalEnergyWeight`, TRX staked for energy network-wide
+    pub total_energy_weight: i64,
+    /// `TotalNetLimit`
+    pub total_net_limit: i64,
+    /// `TotalNetWeight`, TRX staked for bandwidth network-wide
+    pub total_net_weight: i64,
+}
+
+/// TRX to stake for a resource, see [`StakePlanner::plan`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StakePlan {
+    pub resource: ResourceCode,
+    /// TRX to freeze or delegate
+    pub amount: Trx,
+    /// Daily resource `amount` yields once staked
+    pub daily: i64,
+}
+
+impl StakePlanner {
+    pub fn new(resources: &AccountResourceUsage) -> Self {
+        StakePlanner {
+            total_energy_limit: resources.total_energy_limit,
+            total_energy_weight: resources.total_energy_weight,
+            total_net_limit: resources.total_net_limit,
+            total_net_weight: resources.total_net_weight,
+        }
+    }
+    fn totals(&self, resource: ResourceCode) -> Option<(i64, i64)> {
+        match resource {
+            ResourceCode::Energy => {
+                Some((self.total_energy_limit, self.total_energy_weight))
+            }
+            ResourceCode::Bandwidth => {
+                Some((self.total_net_limit, self.total_net_weight))
+            }
+            ResourceCode::TronPower => None,
+        }
+    }
+    /// Daily resource yielded by `amount` which is already staked
+    /// (included in total weight).
+    pub fn daily_yield(&self, amount: Trx, resource: ResourceCode) -> i64 {
+        let Some((limit, weight)) = self.totals(resource) else {
+            return 0;
+        };
+        if weight <= 0 {
+            return 0;
+        }
+        let staked = amount.to_sun() / SUN_PER_TRX;
+        (staked as i128 * limit as i128 / weight as i128) as i64
+    }
+    /// TRX, already staked, needed to provide at least `daily` resource,
+    /// e.g. for delegation of frozen TRX. `None` if `resource` can't be
+    /// staked for.
+    pub fn staked_for(
+        &self,
+        daily: i64,
+        resource: ResourceCode,
+    ) -> Option<Trx> {
+        let (limit, weight) = self.totals(resource)?;
+        if limit <= 0 {
+            return None;
+        }
+        let (daily, limit) = (daily.max(0) as i128, limit as i128);
+        let staked = (daily * weight.max(0) as i128 + limit - 1) / limit;
+        let staked = i64::try_from(staked).ok()?;
+        Some(Trx::from_sun(staked.checked_mul(SUN_PER_TRX)?))
+    }
+    /// TRX to stake to get at least `daily` resource. New stake increases
+    /// total weight, this is taken into account. `None` if `daily` can't be
+    /// reached or `resource` can't be staked for.
+    pub fn plan(
+        &self,
+        daily: i64,
+        resource: ResourceCode,
+    ) -> Option<StakePlan> {
+        let (limit, weight) = self.totals(resource)?;
+        let daily = daily.max(0);
+        if daily >= limit {
+            return None;
+        }
+        // staked * limit / (weight + staked) >= daily
+        let (daily_, limit_, weight_) =
+            (daily as i128, limit as i128, weight.max(0) as i128);
+        let denominator = limit_ - daily_;
+        let staked = (daily_ * weight_ + denominator - 1) / denominator;
+        let staked = i64::try_from(staked).ok()?;
+
+        let planner = match resource {
+            ResourceCode::Energy => StakePlanner {
+                total_energy_weight: weight.max(0) + staked,
+                ..*self
+            },
+            _ => StakePlanner {
+                total_net_weight: weight.max(0) + staked,
+                ..*self
+            },
+        };
+        let amount = Trx::from_sun(staked.checked_mul(SUN_PER_TRX)?);
+        Some(StakePlan {
+            resource,
+            amount,
+            daily: planner.daily_yield(amount, resource),
+        })
+    }
+}
+
+impl StakePlan {
+    /// Freeze builder with amount and resource of this plan
+    pub fn freeze<'a, P, S>(
+        &self,
+        client: &'a Client<P, S>,
+    ) -> FreezeBalanceBuilder<
+        'a,
+        P,
+        S,
+        freeze_balance_builder::SetResource<freeze_balance_builder::SetAmount>,
+    >
+    where
+        P: TronProvider,
+        S: PrehashSigner,
+    {
+        client
+            .freeze_balance()
+            .amount(self.amount)
+            .resource(self.resource)
+    }
+    /// Delegate builder with amount and resource of this plan, owner must
+    /// have `amount` frozen for `resource`.
+    pub fn delegate<'a, P, S>(
+        &self,
+        client: &'a Client<P, S>,
+        receiver: TronAddress,
+    ) -> DelegateBuilder<
+        'a,
+        P,
+        S,
+        delegate_builder::SetReceiver<
+            delegate_builder::SetResource<delegate_builder::SetAmount>,
+        >,
+    >
+    where
+        P: TronProvider,
+        S: PrehashSigner,
+    {
+        client
+            .delegate()
+            .amount(self.amount)
+            .resource(self.resource)
+            .receiver(receiver)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plan_covers_daily_energy() {
+        let planner = StakePlanner {
+            total_energy_limit: 180_000_000_000,
+            total_energy_weight: 19_000_000_000,
+            total_net_limit: 43_200_000_000,
+            total_net_weight: 26_000_000_000,
+        };
+        let plan = planner.plan(65_000, ResourceCode::Energy);
+        assert!(plan.is_some_and(|p| p.daily >= 65_000));
+        assert_eq!(
+            planner.daily_yield(Trx::from_sun(1_000_000), ResourceCode::Energy),
+            9
+        );
+        assert_eq!(planner.plan(1, ResourceCode::TronPower), None);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod revert;
 pub mod token;
 pub mod trc20;
 
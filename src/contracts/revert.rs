@@ -0,0 +1,159 @@
+use alloy_primitives::keccak256;
+use alloy_sol_types::SolError;
+
+use crate::domain::contract::{Abi, EntryType};
+
+/// Message of the node for a `REVERT` without a runtime error
+const REVERT_MESSAGE: &str = "REVERT opcode executed";
+
+/// Reason of a failed contract call
+#[derive(Debug, Clone, PartialEq)]
+pub enum Revert {
+    /// `Error(string)` from `require` and `revert("...")`
+    Error(String),
+    /// `Panic(uint256)` from `assert` and checked arithmetic
+    Panic(alloy_sol_types::Panic),
+    /// Custom error, `name` is resolved from the contract ABI if known
+    Custom {
+        selector: [u8; 4],
+        name: Option<String>,
+        /// Return data including the selector
+        data: Vec<u8>,
+    },
+    /// Reverted without data
+    Empty,
+    /// Failed without reverting, e.g. out of energy
+    Runtime(String),
+}
+
+impl Revert {
+    /// Decodes return data of a failed call. `message` is the runtime error
+    /// reported by the node.
+    pub fn decode(data: &[u8], message: &str, abi: Option<&Abi>) -> Self {
+        let Some(selector) = data.get(..4) else {
+            return match message {
+                "" | REVERT_MESSAGE => Revert::Empty,
+                message => Revert::Runtime(message.into()),
+            };
+        };
+        if selector == alloy_sol_types::Revert::SELECTOR
+            && let Ok(revert) = alloy_sol_types::Revert::abi_decode(data)
+        {
+            return Revert::Error(revert.reason);
+        }
+        if selector == alloy_sol_types::Panic::SELECTOR
+            && let Ok(panic) = alloy_sol_types::Panic::abi_decode(data)
+        {
+            return Revert::Panic(panic);
+        }
+
+        let selector: [u8; 4] = selector.try_into().unwrap_or_default();
+        let name = abi.and_then(|abi| {
+            abi.entrys
+                .iter()
+                .filter(|e| e.entry_type == EntryType::Error)
+                .find(|e| {
+                    let types: Vec<&str> = e
+                        .inputs
+                        .iter()
+                        .map(|p| p.param_type.as_str())
+                        .collect();
+                    let signature = format!("{}({})", e.name, types.join(","));
+                    keccak256(signature)[..4] == selector
+                })
+                .map(|e| e.name.clone())
+        });
+        Revert::Custom {
+            selector,
+            name,
+            data: data.to_vec(),
+        }
+    }
+    /// Decodes a custom error with its generated type
+    pub fn decode_custom<E: SolError>(&self) -> Option<E> {
+        match self {
+            Revert::Custom { data, .. } => E::abi_decode(data).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Revert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Revert::Error(reason) => write!(f, "{reason}"),
+            Revert::Panic(panic) => match panic.kind() {
+                Some(kind) => write!(f, "panic: {kind}"),
+                None => write!(f, "panic: {}", panic.code),
+            },
+            Revert::Custom {
+                name: Some(name), ..
+            } => write!(f, "custom error {name}"),
+            Revert::Custom { selector, .. } => {
+                write!(f, "custom error 0x{}", hex::encode(selector))
+            }
+            Revert::Empty => write!(f, "reverted without reason"),
+            Revert::Runtime(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloy_sol_types::sol;
+
+    use super::*;
+    use crate::domain::contract::{Entry, Param};
+
+    sol! {
+        error InsufficientAllowance(uint256 allowance, uint256 needed);
+    }
+
+    #[test]
+    fn decode_reverts() {
+        let error = alloy_sol_types::Revert::from("not owner").abi_encode();
+        assert_eq!(
+            Revert::decode(&error, REVERT_MESSAGE, None),
+            Revert::Error("not owner".into())
+        );
+
+        let panic = alloy_sol_types::Panic::from(0x11).abi_encode();
+        assert_eq!(
+            Revert::decode(&panic, REVERT_MESSAGE, None).to_string(),
+            "panic: arithmetic underflow or overflow"
+        );
+
+        let custom = InsufficientAllowance {
+            allowance: alloy_primitives::U256::from(1),
+            needed: alloy_primitives::U256::from(2),
+        }
+        .abi_encode();
+        let abi = Abi {
+            entrys: vec![Entry {
+                name: "InsufficientAllowance".into(),
+                inputs: vec![
+                    Param {
+                        param_type: "uint256".into(),
+                        ..Default::default()
+                    };
+                    2
+                ],
+                entry_type: EntryType::Error,
+                ..Default::default()
+            }],
+        };
+        let revert = Revert::decode(&custom, REVERT_MESSAGE, Some(&abi));
+        assert_eq!(revert.to_string(), "custom error InsufficientAllowance");
+        assert!(
+            revert
+                .decode_custom::<InsufficientAllowance>()
+                .is_some_and(|e| e.needed.to::<u64>() == 2)
+        );
+
+        assert_eq!(Revert::decode(&[], REVERT_MESSAGE, None), Revert::Empty);
+        assert_eq!(
+            Revert::decode(&[], "OUT_OF_ENERGY", None),
+            Revert::Runtime("OUT_OF_ENERGY".into())
+        );
+    }
+}
@@ -31,24 +31,42 @@ pub trait Token:
 
 pub trait TokenRegistry {
     fn resolve_token(&self, address: &TronAddress) -> Option<TokenKind>;
+    /// TRC-10 asset by its id, unknown assets by default
+    fn resolve_asset(&self, _asset_id: &str) -> Option<TokenKind> {
+        None
+    }
 }
 
 #[derive(Clone, Debug)]
 pub enum TokenKind {
     Usdt,
     Usdc,
+    /// TRC-10 asset with its id, see [`TokenRegistry::resolve_asset`]
+    Trc10(String),
     Other(String),
 }
 
 #[derive(Default, Clone)]
 pub struct InMemoryTokenRegistry {
     pub map: Arc<HashMap<TronAddress, TokenKind>>,
+    /// TRC-10 assets by id
+    pub assets: Arc<HashMap<String, TokenKind>>,
+}
+
+impl InMemoryTokenRegistry {
+    pub fn with_assets(self, assets: HashMap<String, TokenKind>) -> Self {
+        InMemoryTokenRegistry {
+            assets: Arc::new(assets),
+            ..self
+        }
+    }
 }
 
 impl From<HashMap<TronAddress, TokenKind>> for InMemoryTokenRegistry {
     fn from(value: HashMap<TronAddress, TokenKind>) -> Self {
         InMemoryTokenRegistry {
             map: Arc::new(value),
+            ..Default::default()
         }
     }
 }
@@ -57,4 +75,7 @@ impl TokenRegistry for InMemoryTokenRegistry {
     fn resolve_token(&self, address: &TronAddress) -> Option<TokenKind> {
         self.map.get(address).cloned()
     }
+    fn resolve_asset(&self, asset_id: &str) -> Option<TokenKind> {
+        self.assets.get(asset_id).cloned()
+    }
 }
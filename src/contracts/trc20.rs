@@ -19,7 +19,6 @@ use crate::{
     error::Error,
     provider::TronProvider,
     signer::PrehashSigner,
-    trx,
 };
 
 use super::TryFromData;
@@ -386,7 +385,10 @@ where
             }
         }
 
-        let latest_block = transfer.client.provider.get_now_block().await?;
+        let (latest_block, fee_parameters) = tokio::try_join!(
+            transfer.client.provider.get_now_block(),
+            transfer.client.fee_parameters()
+        )?;
         let transaction = Transaction::new(
             Contract {
                 contract_type:
@@ -409,7 +411,7 @@ where
         );
         let activation_checks = vec![ActivationFeeCheck {
             address: transfer.to,
-            fee: trx!(0.1 TRX),
+            fee: fee_parameters.create_account_fee,
         }];
         PendingTransaction::new(
             transfer.client,
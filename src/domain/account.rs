@@ -216,6 +216,50 @@ impl Account {
         }
     }
 
+    /// Votes the account can cast, in whole TRX. Same as the node's
+    /// `getAllTronPower`: TRX frozen for `TRON_POWER` always counts, other
+    /// stake (including delegated to others, Stake 2.0 and legacy) counts
+    /// unless the account re-voted after the new resource model, which
+    /// fixes it in `old_tron_power`.
+    pub fn tron_power(&self) -> i64 {
+        const SUN_PER_TRX: i64 = 1_000_000;
+        let resource = &self.account_resource;
+        let (tron_power_v2, frozen_v2): (Vec<&FreezeV2>, Vec<&FreezeV2>) = self
+            .frozen_v2
+            .iter()
+            .partition(|f| f.freeze_type == ResourceCode::TronPower);
+        let frozen_v2: Trx = frozen_v2.iter().map(|f| f.amount).sum();
+        let tron_power_v2: Trx = tron_power_v2.iter().map(|f| f.amount).sum();
+        let frozen_v1: Trx = self.frozen.iter().map(|f| f.frozen_balance).sum();
+        let energy_v1 = resource
+            .frozen_balance_for_energy
+            .as_ref()
+            .map(|f| f.frozen_balance)
+            .unwrap_or_default();
+        let tron_power_v1 = self
+            .tron_power
+            .as_ref()
+            .map(|f| f.frozen_balance)
+            .unwrap_or_default();
+
+        let tron_power = tron_power_v1.to_sun() + tron_power_v2.to_sun();
+        let sun = match self.old_tron_power {
+            -1 => tron_power,
+            0 => {
+                frozen_v2.to_sun()
+                    + self.delegated_frozen_v2_balance_for_bandwidth.to_sun()
+                    + resource.delegated_frozen_v2_balance_for_energy.to_sun()
+                    + frozen_v1.to_sun()
+                    + energy_v1.to_sun()
+                    + self.delegated_frozen_balance_for_bandwidth.to_sun()
+                    + resource.delegated_frozen_balance_for_energy
+                    + tron_power
+            }
+            old => old + tron_power,
+        };
+        sun / SUN_PER_TRX
+    }
+
     /// 100% reliable account existence check
     pub fn exists(&self) -> bool {
         match self.account_type {
@@ -262,3 +306,40 @@ pub enum AccountStatus {
     /// Special case for contract accounts
     Contract(OffsetDateTime),
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tron_power_counts_legacy_stake() {
+        let trx = |amount: i64| Trx::from_sun(amount * 1_000_000);
+        let mut account = Account {
+            frozen: vec![Frozen {
+                frozen_balance: trx(10),
+                ..Default::default()
+            }],
+            tron_power: Some(Frozen {
+                frozen_balance: trx(5),
+                ..Default::default()
+            }),
+            frozen_v2: vec![
+                FreezeV2 {
+                    freeze_type: ResourceCode::Energy,
+                    amount: trx(100),
+                },
+                FreezeV2 {
+                    freeze_type: ResourceCode::TronPower,
+                    amount: trx(1),
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(account.tron_power(), 116);
+        // Fixed after voting with the new resource model
+        account.old_tron_power = 50_000_000;
+        assert_eq!(account.tron_power(), 56);
+        account.old_tron_power = -1;
+        assert_eq!(account.tron_power(), 6);
+    }
+}
@@ -5,7 +5,7 @@ use crate::domain::{
     address::TronAddress, transaction::Transaction,
 };
 
-use super::transaction::TransactionExtention;
+use super::transaction::{TransactionExtention, TransactionInfo};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct RawBlockHeader {
@@ -38,6 +38,14 @@ pub struct BlockExtention {
 }
 
 impl BlockExtention {
+    /// Pairs transactions with receipts of the block
+    pub fn attach_receipts(&mut self, infos: Vec<TransactionInfo>) {
+        let mut infos: std::collections::HashMap<_, _> =
+            infos.into_iter().map(|info| (info.id, info)).collect();
+        for tx in &mut self.transactions {
+            tx.info = infos.remove(&tx.txid);
+        }
+    }
     pub(crate) fn calculate_ref_block_bytes(&self) -> RefBlockBytes {
         let last_2_bytes = (self.block_header.raw_data.number & 0xFFFF) as u16;
         last_2_bytes.to_be_bytes().into()
@@ -61,3 +69,70 @@ pub struct Block {
     pub transactions: Vec<Transaction>,
     pub block_header: Option<BlockHeader>,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::protocol;
+
+    fn tx(txid: u8) -> TransactionExtention {
+        TransactionExtention {
+            transaction: None,
+            txid: Hash32::from([txid; 32]),
+            constant_result: Vec::new(),
+            energy_used: 0,
+            energy_penalty: 0,
+            result: None,
+            logs: Vec::new(),
+            internal_transactions: Vec::new(),
+            info: None,
+        }
+    }
+
+    fn info(id: u8) -> Option<TransactionInfo> {
+        let info = protocol::TransactionInfo {
+            id: vec![id; 32],
+            ..Default::default()
+        };
+        TransactionInfo::try_from(info).ok()
+    }
+
+    #[test]
+    fn receipts_are_paired_by_txid() {
+        let mut block = BlockExtention {
+            transactions: vec![tx(1), tx(2), tx(3)],
+            block_header: BlockHeader {
+                raw_data: RawBlockHeader {
+                    timestamp: OffsetDateTime::UNIX_EPOCH,
+                    tx_trie_root: Hash32::default(),
+                    parent_hash: Hash32::default(),
+                    number: 7,
+                    witness_id: 0,
+                    witness_address: TronAddress::default(),
+                    version: 0,
+                    account_state_root: Hash32::default(),
+                },
+                witness_signature: None,
+                pq_auth_sig: None,
+            },
+            blockid: Hash32::default(),
+        };
+        // Out of order, one missing and one of another transaction
+        let infos = [info(3), info(1), info(4)];
+        block.attach_receipts(infos.into_iter().flatten().collect());
+
+        let paired: Vec<_> = block
+            .transactions
+            .iter()
+            .map(|tx| tx.info.as_ref().map(|info| info.id))
+            .collect();
+        assert_eq!(
+            paired,
+            [
+                Some(Hash32::from([1; 32])),
+                None,
+                Some(Hash32::from([3; 32]))
+            ]
+        );
+    }
+}
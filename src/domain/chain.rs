@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use time::OffsetDateTime;
+
+use super::proposal::ChainParameter;
+use super::trx::Trx;
+use crate::utility::TronOffsetDateTime;
+
+/// Conversion of a raw chain parameter value
+trait ChainValue: Sized {
+    /// `None` if the parameter is required but missing
+    fn from_raw(value: Option<i64>) -> Option<Self>;
+    fn to_raw(&self) -> Option<i64>;
+}
+
+impl ChainValue for i64 {
+    fn from_raw(value: Option<i64>) -> Option<Self> {
+        value
+    }
+    fn to_raw(&self) -> Option<i64> {
+        Some(*self)
+    }
+}
+
+impl ChainValue for Trx {
+    fn from_raw(value: Option<i64>) -> Option<Self> {
+        value.map(Trx::from_sun)
+    }
+    fn to_raw(&self) -> Option<i64> {
+        Some(self.to_sun())
+    }
+}
+
+impl ChainValue for bool {
+    fn from_raw(value: Option<i64>) -> Option<Self> {
+        value.map(|v| v != 0)
+    }
+    fn to_raw(&self) -> Option<i64> {
+        Some(*self as i64)
+    }
+}
+
+impl ChainValue for time::Duration {
+    fn from_raw(value: Option<i64>) -> Option<Self> {
+        value.map(time::Duration::milliseconds)
+    }
+    fn to_raw(&self) -> Option<i64> {
+        Some(self.whole_milliseconds() as i64)
+    }
+}
+
+/// Optional parameters, added by later proposals
+impl<T: ChainValue> ChainValue for Option<T> {
+    fn from_raw(value: Option<i64>) -> Option<Self> {
+        Some(value.and_then(|v| T::from_raw(Some(v))))
+    }
+    fn to_raw(&self) -> Option<i64> {
+        self.as_ref().and_then(T::to_raw)
+    }
+}
+
+macro_rules! chain_parameters {
+    ($($(#[$doc:meta])* $field:ident: $ty:ty = $param:ident,)*) => {
+        /// Network parameters, as returned by `GetChainParameters`
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct ChainParameters {
+            $(
+                $(#[$doc])*
+                #[doc = concat!("[`ChainParameter::", stringify!($param), "`]")]
+                pub $field: $ty,
+            )*
+            /// Parameters without a typed field
+            pub other: HashMap<String, i64>,
+        }
+
+        impl ChainParameters {
+            /// Raw value by `GetChainParameters` key
+            pub fn get(&self, key: &str) -> Option<i64> {
+                match ChainParameter::from_key(key) {
+                    $(Some(ChainParameter::$param) => self.$field.to_raw(),)*
+                    _ => self.other.get(key).copied(),
+                }
+            }
+            /// Fails with the key of the first missing required parameter
+            pub fn from_raw(
+                mut raw: HashMap<String, i64>,
+            ) -> Result<Self, &'static str> {
+                let key =
+                    |param: ChainParameter| param.key().unwrap_or_default();
+                Ok(ChainParameters {
+                    $($field: ChainValue::from_raw(
+                        raw.remove(key(ChainParameter::$param)),
+                    )
+                    .ok_or(key(ChainParameter::$param))?,)*
+                    other: raw,
+                })
+            }
+        }
+    };
+}
+
+// Only fee parameters are required, other ones may be absent on private
+// networks or older nodes
+chain_parameters! {
+    /// Burned for bandwidth of account creation without staked bandwidth
+    create_account_fee: Trx = CreateAccountFee,
+    /// Price of one bandwidth point
+    transaction_fee: Trx = TransactionFee,
+    /// Charged by system contracts creating an account
+    create_new_account_fee_in_system_contract: Trx =
+        CreateNewAccountFeeInSystemContract,
+    /// Price of one energy unit
+    energy_fee: Trx = EnergyFee,
+    maintenance_time_interval: Option<time::Duration> =
+        MaintenanceTimeInterval,
+    /// Burned on witness creation
+    account_upgrade_cost: Option<Trx> = AccountUpgradeCost,
+    asset_issue_fee: Option<Trx> = AssetIssueFee,
+    /// Paid to the block producer
+    witness_pay_per_block: Option<Trx> = WitnessPayPerBlock,
+    /// Shared among top 127 witnesses by votes
+    witness_127_pay_per_block: Option<Trx> = Witness127PayPerBlock,
+    create_new_account_bandwidth_rate: Option<i64> =
+        CreateNewAccountBandwidthRate,
+    exchange_create_fee: Option<Trx> = ExchangeCreateFee,
+    /// Milliseconds
+    max_cpu_time_of_one_tx: Option<i64> = MaxCpuTimeOfOneTx,
+    total_energy_limit: Option<i64> = TotalEnergyLimit,
+    /// Daily energy shared by all stakers
+    total_energy_current_limit: Option<i64> = TotalEnergyCurrentLimit,
+    update_account_permission_fee: Option<Trx> = UpdateAccountPermissionFee,
+    /// Charged for transactions with more than one signature
+    multi_sign_fee: Option<Trx> = MultiSignFee,
+    market_sell_fee: Option<Trx> = MarketSellFee,
+    market_cancel_fee: Option<Trx> = MarketCancelFee,
+    max_fee_limit: Option<Trx> = MaxFeeLimit,
+    /// Daily free bandwidth per account
+    free_net_limit: Option<i64> = FreeNetLimit,
+    /// Daily bandwidth shared by all stakers
+    total_net_limit: Option<i64> = TotalNetLimit,
+    /// Charged for transactions with non-empty memo
+    memo_fee: Option<Trx> = MemoFee,
+    unfreeze_delay_days: Option<i64> = UnfreezeDelayDays,
+    allow_dynamic_energy: Option<bool> = AllowDynamicEnergy,
+    dynamic_energy_threshold: Option<i64> = DynamicEnergyThreshold,
+    dynamic_energy_increase_factor: Option<i64> = DynamicEnergyIncreaseFactor,
+    dynamic_energy_max_factor: Option<i64> = DynamicEnergyMaxFactor,
+    /// Blocks
+    max_delegate_lock_period: Option<i64> = MaxDelegateLockPeriod,
+}
+
+/// `GetDynamicProperties`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DynamicProperties {
+    pub last_solidity_block_num: i64,
+}
+
+/// History of a resource price, as returned by `GetEnergyPrices` and
+/// `GetBandwidthPrices`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceHistory {
+    /// Price and the time it is effective from, sorted by time
+    pub prices: Vec<(OffsetDateTime, Trx)>,
+}
+
+impl PriceHistory {
+    /// Parses `"<millis>:<sun>,<millis>:<sun>,..."`
+    pub fn parse(prices: &str) -> Option<Self> {
+        let mut prices = prices
+            .split(',')
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                let (time, price) = p.split_once(':')?;
+                let time =
+                    OffsetDateTime::try_from_tron(time.trim().parse().ok()?)
+                        .ok()?;
+                Some((time, Trx::from_sun(price.trim().parse().ok()?)))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        prices.sort_by_key(|(time, _)| *time);
+        Some(PriceHistory { prices })
+    }
+    /// Latest price
+    pub fn current(&self) -> Option<Trx> {
+        self.prices.last().map(|(_, price)| *price)
+    }
+    /// Price effective at `time`, `None` if `time` precedes the history
+    pub fn price_at(&self, time: OffsetDateTime) -> Option<Trx> {
+        self.prices
+            .iter()
+            .rev()
+            .find(|(from, _)| *from <= time)
+            .map(|(_, price)| *price)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_chain_parameters() {
+        let mut raw: HashMap<String, i64> = [
+            ("getMaintenanceTimeInterval", 21_600_000),
+            ("getAccountUpgradeCost", 9_999_000_000),
+            ("getCreateAccountFee", 100_000),
+            ("getTransactionFee", 1_000),
+            ("getAssetIssueFee", 1_024_000_000),
+            ("getWitnessPayPerBlock", 8_000_000),
+            ("getWitness127PayPerBlock", 128_000_000),
+            ("getCreateNewAccountFeeInSystemContract", 1_000_000),
+            ("getCreateNewAccountBandwidthRate", 1),
+            ("getEnergyFee", 100),
+            ("getExchangeCreateFee", 1_024_000_000),
+            ("getMaxCpuTimeOfOneTx", 80),
+            ("getTotalEnergyLimit", 90_000_000_000),
+            ("getTotalEnergyCurrentLimit", 90_000_000_000),
+            ("getUpdateAccountPermissionFee", 100_000_000),
+            ("getMultiSignFee", 1_000_000),
+            ("getAllowDynamicEnergy", 1),
+            ("getSomethingNew", 7),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+
+        let params = ChainParameters::from_raw(raw.clone());
+        assert!(params.as_ref().is_ok_and(|p| {
+            p.energy_fee == Trx::from_sun(100)
+                && p.allow_dynamic_energy == Some(true)
+                && p.memo_fee.is_none()
+                && p.get("getSomethingNew") == Some(7)
+                && p.get("getEnergyFee") == Some(100)
+        }));
+
+        raw.remove("getWitness127PayPerBlock");
+        let params = ChainParameters::from_raw(raw.clone());
+        assert!(
+            params
+                .as_ref()
+                .is_ok_and(|p| p.witness_127_pay_per_block.is_none())
+        );
+        raw.remove("getEnergyFee");
+        assert_eq!(ChainParameters::from_raw(raw), Err("getEnergyFee"));
+
+        let history = PriceHistory::parse("0:100,1606537680000:40");
+        assert_eq!(
+            history.as_ref().and_then(|h| h.current()),
+            Some(Trx::from_sun(40))
+        );
+        let at = OffsetDateTime::try_from_tron(1_600_000_000_000).ok();
+        assert_eq!(
+            history.zip(at).and_then(|(h, at)| h.price_at(at)),
+            Some(Trx::from_sun(100))
+        );
+    }
+}
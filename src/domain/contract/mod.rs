@@ -314,7 +314,7 @@ pub struct WithdrawExpireUnfreezeContract {
     pub owner_address: TronAddress,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ResourceCode {
     #[default]
     Bandwidth = 0,
@@ -545,10 +545,30 @@ pub enum StateMutabilityType {
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub struct ContractState {
     pub energy_usage: i64,
+    /// Dynamic energy penalty, scaled by [`ContractState::ENERGY_FACTOR_SCALE`]
     pub energy_factor: i64,
     pub update_cycle: i64,
 }
 
+impl ContractState {
+    pub const ENERGY_FACTOR_SCALE: i64 = 10_000;
+
+    /// Energy including the dynamic energy penalty of the contract
+    pub fn apply_energy_factor(&self, energy: i64) -> i64 {
+        let factor = Self::ENERGY_FACTOR_SCALE + self.energy_factor.max(0);
+        ((energy as i128) * (factor as i128)
+            / (Self::ENERGY_FACTOR_SCALE as i128)) as i64
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct SmartContractDataWrapper {
+    /// `None` if there is no contract at the address
+    pub smart_contract: Option<SmartContract>,
+    pub runtime_code: Vec<u8>,
+    pub contract_state: Option<ContractState>,
+}
+
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct CreateSmartContract {
     pub owner_address: TronAddress,
@@ -631,3 +651,23 @@ pub struct ContractArtifact {
     pub schema_version: String,
     pub updated_at: String,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn energy_factor_is_applied() {
+        let state = |energy_factor| ContractState {
+            energy_factor,
+            ..Default::default()
+        };
+        assert_eq!(state(0).apply_energy_factor(1_000), 1_000);
+        assert_eq!(state(-2_000).apply_energy_factor(1_000), 1_000);
+        assert_eq!(state(10_000).apply_energy_factor(1_000), 2_000);
+        assert_eq!(state(3_400).apply_energy_factor(1_000), 1_340);
+        // 1_001 * 1.3333 = 1_334.63, rounded down
+        assert_eq!(state(3_333).apply_energy_factor(1_001), 1_334);
+        assert_eq!(state(1).apply_energy_factor(9_999), 9_999);
+    }
+}
@@ -3,6 +3,8 @@ use crate::{
     provider::TronProvider, signer::PrehashSigner,
 };
 
+use super::chain::ChainParameters;
+use super::transaction::TransactionInfo;
 use super::trx::Trx;
 
 #[derive(Debug, Clone, thiserror::Error)]
@@ -177,3 +179,261 @@ pub(crate) fn calculate_topup(
         })
         .collect()
 }
+
+/// How an [`EnergyEstimate`] was obtained
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnergyEstimateMethod {
+    /// Transaction does not execute a contract
+    NotRequired,
+    /// `EstimateEnergy` RPC
+    EstimateEnergy,
+    /// `energy_used` of a constant call, dynamic energy penalty reported
+    /// by the node is included
+    ConstantCall,
+    /// `energy_used` of a constant call with contract's energy factor
+    /// applied, the node didn't report a penalty
+    ConstantCallWithEnergyFactor,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EnergyEstimate {
+    pub energy: i64,
+    pub method: EnergyEstimateMethod,
+}
+
+/// Fee-related chain parameters
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeeParameters {
+    /// Price of one bandwidth point, `getTransactionFee`
+    pub bandwidth_price: Trx,
+    /// Price of one energy unit, `getEnergyFee`
+    pub energy_price: Trx,
+    /// Bandwidth burned for account creation without staked bandwidth,
+    /// `getCreateAccountFee`
+    pub create_account_fee: Trx,
+    /// Charged by system contracts creating an account,
+    /// `getCreateNewAccountFeeInSystemContract`
+    pub create_new_account_fee_in_system_contract: Trx,
+    /// Staked bandwidth consumed per byte by a transaction creating an
+    /// account, `getCreateNewAccountBandwidthRate`
+    pub create_new_account_bandwidth_rate: i64,
+    /// Charged for transactions with more than one signature,
+    /// `getMultiSignFee`
+    pub multi_sign_fee: Trx,
+    /// Charged for transactions with non-empty memo, `getMemoFee`
+    pub memo_fee: Trx,
+}
+
+impl FeeParameters {
+    pub fn from_chain_parameters(params: &ChainParameters) -> Self {
+        FeeParameters {
+            bandwidth_price: params.transaction_fee,
+            energy_price: params.energy_fee,
+            create_account_fee: params.create_account_fee,
+            create_new_account_fee_in_system_contract: params
+                .create_new_account_fee_in_system_contract,
+            create_new_account_bandwidth_rate: params
+                .create_new_account_bandwidth_rate
+                .unwrap_or(1),
+            multi_sign_fee: params.multi_sign_fee.unwrap_or_default(),
+            // Absent on nodes before the memo fee proposal
+            memo_fee: params.memo_fee.unwrap_or_default(),
+        }
+    }
+    /// Activation cost of an account created by a TRX transfer
+    pub fn activation_fee(&self) -> Trx {
+        self.create_new_account_fee_in_system_contract + self.create_account_fee
+    }
+    /// Fee burned for `bandwidth` with `free` and `staked` bandwidth
+    /// available. A transaction creating an account consumes
+    /// `bandwidth * create_new_account_bandwidth_rate` staked bandwidth
+    /// instead, or burns `create_account_fee` if it is insufficient.
+    pub fn bandwidth_fee(
+        &self,
+        bandwidth: i64,
+        free: i64,
+        staked: i64,
+        creates_account: bool,
+    ) -> Trx {
+        if creates_account {
+            let rate = self.create_new_account_bandwidth_rate.max(1);
+            if staked >= bandwidth.saturating_mul(rate) {
+                Trx::ZERO
+            } else {
+                self.create_account_fee
+            }
+        } else if free >= bandwidth || staked >= bandwidth {
+            Trx::ZERO
+        } else {
+            self.bandwidth_price * bandwidth
+        }
+    }
+}
+
+/// Fees burned by an included transaction, attributed to their cause with
+/// prices effective at its block
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeeAttribution {
+    pub energy_price: Trx,
+    pub bandwidth_price: Trx,
+    /// Energy paid for by burning TRX
+    pub energy_burned: i64,
+    pub energy_fee: Trx,
+    /// Bandwidth paid for by burning TRX
+    pub bandwidth_burned: i64,
+    pub bandwidth_fee: Trx,
+    /// Rest of the fee: activation, multisig, memo and system contract
+    /// fees
+    pub other_fee: Trx,
+}
+
+impl FeeAttribution {
+    pub fn new(
+        info: &TransactionInfo,
+        energy_price: Trx,
+        bandwidth_price: Trx,
+    ) -> Self {
+        let (energy_fee, bandwidth_fee) = info
+            .receipt
+            .as_ref()
+            .map(|r| (Trx::from_sun(r.energy_fee), r.net_fee))
+            .unwrap_or_default();
+        let per_unit = |fee: Trx, price: Trx| {
+            fee.to_sun().checked_div(price.to_sun()).unwrap_or_default()
+        };
+        FeeAttribution {
+            energy_price,
+            bandwidth_price,
+            energy_burned: per_unit(energy_fee, energy_price),
+            energy_fee,
+            bandwidth_burned: per_unit(bandwidth_fee, bandwidth_price),
+            bandwidth_fee,
+            other_fee: info.fee - energy_fee - bandwidth_fee,
+        }
+    }
+    pub fn total(&self) -> Trx {
+        self.energy_fee + self.bandwidth_fee + self.other_fee
+    }
+}
+
+/// Itemized fees a transaction will burn, computed before signing
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FeeBreakdown {
+    /// Exact bandwidth consumed, with all required signatures
+    pub bandwidth: i64,
+    /// Burned if neither free nor staked bandwidth covers `bandwidth`, see
+    /// [`FeeParameters::bandwidth_fee`] for transactions creating an account
+    pub bandwidth_fee: Trx,
+    pub energy: i64,
+    /// Burned for energy not covered by staked energy
+    pub energy_fee: Trx,
+    /// Account activation fees for receivers which do not exist yet,
+    /// `create_account_fee` of a transfer is in `bandwidth_fee`
+    pub activation_fee: Trx,
+    pub multi_sign_fee: Trx,
+    pub memo_fee: Trx,
+}
+
+impl FeeBreakdown {
+    pub fn total(&self) -> Trx {
+        self.bandwidth_fee
+            + self.energy_fee
+            + self.activation_fee
+            + self.multi_sign_fee
+            + self.memo_fee
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn account_creation_is_charged_as_bandwidth() {
+        let params = FeeParameters {
+            bandwidth_price: Trx::from_sun(1_000),
+            create_account_fee: Trx::from_sun(100_000),
+            create_new_account_fee_in_system_contract: Trx::from_sun(1_000_000),
+            create_new_account_bandwidth_rate: 1,
+            ..Default::default()
+        };
+        assert_eq!(params.bandwidth_fee(268, 600, 0, false), Trx::ZERO);
+        assert_eq!(
+            params.bandwidth_fee(268, 0, 0, false),
+            Trx::from_sun(268_000)
+        );
+        // Free bandwidth can't pay for account creation
+        assert_eq!(
+            params.bandwidth_fee(268, 600, 0, true),
+            params.create_account_fee
+        );
+        assert_eq!(params.bandwidth_fee(268, 0, 268, true), Trx::ZERO);
+
+        let breakdown = FeeBreakdown {
+            bandwidth_fee: params.bandwidth_fee(268, 0, 0, true),
+            activation_fee: params.activation_fee() - params.create_account_fee,
+            ..Default::default()
+        };
+        assert_eq!(breakdown.total(), params.activation_fee());
+    }
+
+    #[test]
+    fn fees_are_attributed() {
+        let params = FeeParameters {
+            bandwidth_price: Trx::from_sun(1_000),
+            energy_price: Trx::from_sun(420),
+            create_new_account_fee_in_system_contract: Trx::from_sun(1_000_000),
+            multi_sign_fee: Trx::from_sun(1_000_000),
+            memo_fee: Trx::from_sun(1_000_000),
+            ..Default::default()
+        };
+        let attribution = |breakdown: &FeeBreakdown| {
+            let info = crate::protocol::TransactionInfo {
+                fee: breakdown.total().to_sun(),
+                receipt: Some(crate::protocol::ResourceReceipt {
+                    energy_fee: breakdown.energy_fee.to_sun(),
+                    net_fee: breakdown.bandwidth_fee.to_sun(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            TransactionInfo::try_from(info).ok().map(|info| {
+                FeeAttribution::new(
+                    &info,
+                    params.energy_price,
+                    params.bandwidth_price,
+                )
+            })
+        };
+
+        let mut breakdown = FeeBreakdown {
+            bandwidth: 268,
+            bandwidth_fee: params.bandwidth_price * 268,
+            energy: 10_000,
+            energy_fee: params.energy_price * 10_000,
+            activation_fee: params.create_new_account_fee_in_system_contract,
+            ..Default::default()
+        };
+        let Some(fees) = attribution(&breakdown) else {
+            panic!("valid receipt");
+        };
+        assert_eq!(fees.bandwidth_burned, 268);
+        assert_eq!(fees.bandwidth_fee, Trx::from_sun(268_000));
+        assert_eq!(fees.energy_burned, 10_000);
+        assert_eq!(fees.energy_fee, Trx::from_sun(4_200_000));
+        assert_eq!(fees.other_fee, breakdown.activation_fee);
+        assert_eq!(fees.total(), Trx::from_sun(5_468_000));
+        assert_eq!(fees.total(), breakdown.total());
+
+        breakdown.multi_sign_fee = params.multi_sign_fee;
+        breakdown.memo_fee = params.memo_fee;
+        let Some(fees) = attribution(&breakdown) else {
+            panic!("valid receipt");
+        };
+        assert_eq!(fees.bandwidth_fee, Trx::from_sun(268_000));
+        assert_eq!(fees.energy_fee, Trx::from_sun(4_200_000));
+        assert_eq!(fees.other_fee, Trx::from_sun(3_000_000));
+        assert_eq!(fees.total(), Trx::from_sun(7_468_000));
+        assert_eq!(fees.total(), breakdown.total());
+    }
+}
@@ -0,0 +1,152 @@
+use time::OffsetDateTime;
+
+use super::address::TronAddress;
+use super::transaction::TransactionInfo;
+
+/// Token id of TRX in exchanges
+pub const TRX_TOKEN_ID: &str = "_";
+
+/// Virtual relay supply of the Bancor formula used by the node
+const RELAY_SUPPLY: f64 = 1_000_000_000_000_000_000.0;
+
+/// Bancor exchange between two TRC-10 tokens or a token and TRX
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exchange {
+    pub exchange_id: i64,
+    pub creator_address: TronAddress,
+    pub create_time: OffsetDateTime,
+    pub first_token_id: String,
+    pub first_token_balance: i64,
+    pub second_token_id: String,
+    pub second_token_balance: i64,
+}
+
+/// Expected result of selling into an exchange
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeQuote {
+    pub sell_token_id: String,
+    pub sell_quant: i64,
+    pub buy_token_id: String,
+    pub buy_quant: i64,
+}
+
+impl ExchangeQuote {
+    /// Least acceptable amount with `slippage_bps` (1 = 0.01%) tolerance
+    pub fn min_received(&self, slippage_bps: u32) -> i64 {
+        let bps = 10_000i128 - slippage_bps.min(10_000) as i128;
+        (self.buy_quant as i128 * bps / 10_000) as i64
+    }
+}
+
+impl Exchange {
+    /// Reserve of `token_id` and of the opposite token
+    pub fn balances(&self, token_id: &str) -> Option<(i64, i64)> {
+        if token_id == self.first_token_id {
+            Some((self.first_token_balance, self.second_token_balance))
+        } else if token_id == self.second_token_id {
+            Some((self.second_token_balance, self.first_token_balance))
+        } else {
+            None
+        }
+    }
+    pub fn other_token(&self, token_id: &str) -> Option<&str> {
+        if token_id == self.first_token_id {
+            Some(&self.second_token_id)
+        } else if token_id == self.second_token_id {
+            Some(&self.first_token_id)
+        } else {
+            None
+        }
+    }
+    /// Amount received for selling `quant` of `sell_token_id`, same formula
+    /// as the node. `None` if the token is not traded here.
+    pub fn quote(
+        &self,
+        sell_token_id: &str,
+        quant: i64,
+    ) -> Option<ExchangeQuote> {
+        let (sell_balance, buy_balance) = self.balances(sell_token_id)?;
+        if quant <= 0 || sell_balance <= 0 || buy_balance <= 0 {
+            return None;
+        }
+
+        // Sell token to relay supply
+        let new_balance = (sell_balance + quant) as f64;
+        let relay = (-RELAY_SUPPLY
+            * (1.0 - (1.0 + quant as f64 / new_balance).powf(0.0005)))
+            as i64;
+        // Relay supply to buy token, supply is back to initial after the
+        // relay is both issued and redeemed
+        let buy_quant = (buy_balance as f64
+            * ((1.0 + relay as f64 / RELAY_SUPPLY).powf(2000.0) - 1.0))
+            as i64;
+
+        Some(ExchangeQuote {
+            sell_token_id: sell_token_id.into(),
+            sell_quant: quant,
+            buy_token_id: self.other_token(sell_token_id)?.into(),
+            buy_quant,
+        })
+    }
+    /// Amount of the opposite token moved along with `quant` of `token_id`
+    /// when injecting or withdrawing, keeping the price
+    pub fn paired_amount(&self, token_id: &str, quant: i64) -> Option<i64> {
+        let (balance, other) = self.balances(token_id)?;
+        if balance <= 0 {
+            return None;
+        }
+        i64::try_from(other as i128 * quant as i128 / balance as i128).ok()
+    }
+}
+
+/// Exchanged amounts recorded in the receipt
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExchangeOutcome {
+    pub exchange_id: i64,
+    /// Bought by an exchange transaction
+    pub received: i64,
+    /// Opposite token deposited by an inject
+    pub inject_another: i64,
+    /// Opposite token returned by a withdraw
+    pub withdraw_another: i64,
+}
+
+impl From<&TransactionInfo> for ExchangeOutcome {
+    fn from(info: &TransactionInfo) -> Self {
+        ExchangeOutcome {
+            exchange_id: info.exchange_id,
+            received: info.exchange_received_amount,
+            inject_another: info.exchange_inject_another_amount,
+            withdraw_another: info.exchange_withdraw_another_amount,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn quote_follows_reserves() {
+        let exchange = Exchange {
+            exchange_id: 1,
+            creator_address: TronAddress::default(),
+            create_time: OffsetDateTime::UNIX_EPOCH,
+            first_token_id: TRX_TOKEN_ID.into(),
+            first_token_balance: 1_000_000_000,
+            second_token_id: "1000001".into(),
+            second_token_balance: 1_000_000_000,
+        };
+        let quote = exchange.quote(TRX_TOKEN_ID, 1_000_000);
+        // Close to constant product: 1e9 * 1e6 / (1e9 + 1e6)
+        assert!(quote.as_ref().is_some_and(|q| {
+            (q.buy_quant - 999_000).abs() < 1_000 && q.buy_token_id == "1000001"
+        }));
+        assert!(
+            quote
+                .is_some_and(|q| q.min_received(100) == q.buy_quant * 99 / 100)
+        );
+        assert_eq!(exchange.quote("1000002", 1), None);
+        assert_eq!(exchange.paired_amount("1000001", 10), Some(10));
+    }
+}
@@ -0,0 +1,171 @@
+use time::OffsetDateTime;
+
+use super::Hash32;
+use super::address::TronAddress;
+use super::transaction::{MarketOrderDetail, TransactionInfo};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MarketOrderState {
+    #[default]
+    Active,
+    /// Filled or remaining amount is too small to trade
+    Inactive,
+    Canceled,
+}
+
+/// Order of the on-chain DEX
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketOrder {
+    pub order_id: Hash32,
+    pub owner_address: TronAddress,
+    pub create_time: OffsetDateTime,
+    /// TRC-10 asset id or `"_"` for TRX
+    pub sell_token_id: String,
+    pub sell_token_quantity: i64,
+    pub buy_token_id: String,
+    /// Least amount to receive for the whole order
+    pub buy_token_quantity: i64,
+    pub sell_token_quantity_remain: i64,
+    /// Remainder returned to the owner when the order became inactive
+    pub sell_token_quantity_return: i64,
+    pub state: MarketOrderState,
+}
+
+impl MarketOrder {
+    /// Sell token amount already traded
+    pub fn filled_quantity(&self) -> i64 {
+        self.sell_token_quantity
+            - self.sell_token_quantity_remain
+            - self.sell_token_quantity_return
+    }
+    pub fn is_filled(&self) -> bool {
+        self.state == MarketOrderState::Inactive
+            && self.sell_token_quantity_return == 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MarketPair {
+    pub sell_token_id: String,
+    pub buy_token_id: String,
+}
+
+impl MarketPair {
+    pub fn new(
+        sell_token_id: impl Into<String>,
+        buy_token_id: impl Into<String>,
+    ) -> Self {
+        MarketPair {
+            sell_token_id: sell_token_id.into(),
+            buy_token_id: buy_token_id.into(),
+        }
+    }
+}
+
+/// Price level of a pair, `buy_token_quantity` per `sell_token_quantity`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarketPrice {
+    pub sell_token_quantity: i64,
+    pub buy_token_quantity: i64,
+}
+
+/// Matches of an order at placement, as recorded in the receipt
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketFill {
+    pub order_id: Hash32,
+    /// Sell token given to makers
+    pub sold: i64,
+    /// Buy token received from makers
+    pub bought: i64,
+    pub details: Vec<MarketOrderDetail>,
+}
+
+impl MarketFill {
+    /// `None` if the receipt is not of a `MarketSellAssetContract`
+    pub fn from_info(info: &TransactionInfo) -> Option<Self> {
+        let order_id = info.order_id.parse().ok()?;
+        // Fill quantities are from the maker side
+        let (sold, bought) =
+            info.order_details.iter().fold((0, 0), |(sold, bought), d| {
+                (sold + d.fill_buy_quantity, bought + d.fill_sell_quantity)
+            });
+        Some(MarketFill {
+            order_id,
+            sold,
+            bought,
+            details: info.order_details.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::protocol;
+
+    fn order(
+        remain: i64,
+        returned: i64,
+        state: MarketOrderState,
+    ) -> MarketOrder {
+        MarketOrder {
+            order_id: Hash32::default(),
+            owner_address: TronAddress::default(),
+            create_time: OffsetDateTime::UNIX_EPOCH,
+            sell_token_id: "_".into(),
+            sell_token_quantity: 100,
+            buy_token_id: "1000001".into(),
+            buy_token_quantity: 50,
+            sell_token_quantity_remain: remain,
+            sell_token_quantity_return: returned,
+            state,
+        }
+    }
+
+    #[test]
+    fn order_fill_progress() {
+        let partial = order(40, 0, MarketOrderState::Active);
+        assert_eq!(partial.filled_quantity(), 60);
+        assert!(!partial.is_filled());
+
+        let filled = order(0, 0, MarketOrderState::Inactive);
+        assert_eq!(filled.filled_quantity(), 100);
+        assert!(filled.is_filled());
+
+        // Dust left after matching is returned, not traded
+        let returned = order(0, 30, MarketOrderState::Inactive);
+        assert_eq!(returned.filled_quantity(), 70);
+        assert!(!returned.is_filled());
+    }
+
+    #[test]
+    fn fill_is_summed_from_maker_side() {
+        let taker = [7; 32];
+        let detail = |maker: u8, sell, buy| protocol::MarketOrderDetail {
+            maker_order_id: vec![maker; 32],
+            taker_order_id: taker.to_vec(),
+            fill_sell_quantity: sell,
+            fill_buy_quantity: buy,
+        };
+        let info = protocol::TransactionInfo {
+            order_id: taker.to_vec(),
+            // Makers sold 30 + 20 asset for 60 + 40 TRX
+            order_details: vec![detail(1, 30, 60), detail(2, 20, 40)],
+            ..Default::default()
+        };
+        let fill = TransactionInfo::try_from(info)
+            .ok()
+            .and_then(|info| MarketFill::from_info(&info));
+
+        assert!(fill.is_some_and(|fill| fill.order_id == Hash32::from(taker)
+            && fill.sold == 100
+            && fill.bought == 50
+            && fill.details.len() == 2));
+
+        let transfer =
+            TransactionInfo::try_from(protocol::TransactionInfo::default());
+        assert!(
+            transfer.is_ok_and(|info| MarketFill::from_info(&info).is_none())
+        );
+    }
+}
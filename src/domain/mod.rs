@@ -8,11 +8,17 @@ use crate::domain::address::TronAddress;
 pub mod account;
 pub mod address;
 pub mod block;
+pub mod chain;
 pub mod contract;
 pub mod estimate;
+pub mod exchange;
+pub mod market;
 pub mod permission;
+pub mod proposal;
+pub mod resource;
 pub mod transaction;
 pub mod trx;
+pub mod witness;
 
 #[macro_export]
 macro_rules! define_fixed_string {
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+use time::OffsetDateTime;
+
+use super::address::TronAddress;
+use super::chain::ChainParameters;
+
+macro_rules! chain_parameters {
+    ($($(#[$doc:meta])* $variant:ident = $id:literal => $key:literal,)*) => {
+        /// Chain parameter changed by committee proposals
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum ChainParameter {
+            $($(#[$doc])* $variant,)*
+            /// Parameter not known to this library
+            Unknown(i64),
+        }
+
+        impl ChainParameter {
+            /// Parameter id used in proposals
+            pub fn id(&self) -> i64 {
+                match self {
+                    $(ChainParameter::$variant => $id,)*
+                    ChainParameter::Unknown(id) => *id,
+                }
+            }
+            pub fn from_id(id: i64) -> Self {
+                match id {
+                    $($id => ChainParameter::$variant,)*
+                    id => ChainParameter::Unknown(id),
+                }
+            }
+            /// Key in `chain_parameters()`
+            pub fn key(&self) -> Option<&'static str> {
+                match self {
+                    $(ChainParameter::$variant => Some($key),)*
+                    ChainParameter::Unknown(_) => None,
+                }
+            }
+            pub fn from_key(key: &str) -> Option<Self> {
+                match key {
+                    $($key => Some(ChainParameter::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+chain_parameters! {
+    MaintenanceTimeInterval = 0 => "getMaintenanceTimeInterval",
+    AccountUpgradeCost = 1 => "getAccountUpgradeCost",
+    CreateAccountFee = 2 => "getCreateAccountFee",
+    /// Bandwidth price, sun
+    TransactionFee = 3 => "getTransactionFee",
+    AssetIssueFee = 4 => "getAssetIssueFee",
+    WitnessPayPerBlock = 5 => "getWitnessPayPerBlock",
+    WitnessStandbyAllowance = 6 => "getWitnessStandbyAllowance",
+    CreateNewAccountFeeInSystemContract = 7
+        => "getCreateNewAccountFeeInSystemContract",
+    CreateNewAccountBandwidthRate = 8 => "getCreateNewAccountBandwidthRate",
+    AllowCreationOfContracts = 9 => "getAllowCreationOfContracts",
+    /// Energy price, sun
+    EnergyFee = 11 => "getEnergyFee",
+    ExchangeCreateFee = 12 => "getExchangeCreateFee",
+    MaxCpuTimeOfOneTx = 13 => "getMaxCpuTimeOfOneTx",
+    AllowUpdateAccountName = 14 => "getAllowUpdateAccountName",
+    AllowSameTokenName = 15 => "getAllowSameTokenName",
+    AllowDelegateResource = 16 => "getAllowDelegateResource",
+    TotalEnergyLimit = 17 => "getTotalEnergyLimit",
+    AllowTvmTransferTrc10 = 18 => "getAllowTvmTransferTrc10",
+    TotalEnergyCurrentLimit = 19 => "getTotalEnergyCurrentLimit",
+    AllowMultiSign = 20 => "getAllowMultiSign",
+    AllowAdaptiveEnergy = 21 => "getAllowAdaptiveEnergy",
+    UpdateAccountPermissionFee = 22 => "getUpdateAccountPermissionFee",
+    MultiSignFee = 23 => "getMultiSignFee",
+    AdaptiveResourceLimitMultiplier = 29
+        => "getAdaptiveResourceLimitMultiplier",
+    ChangeDelegation = 30 => "getChangeDelegation",
+    Witness127PayPerBlock = 31 => "getWitness127PayPerBlock",
+    AdaptiveResourceLimitTargetRatio = 33
+        => "getAdaptiveResourceLimitTargetRatio",
+    ForbidTransferToContract = 35 => "getForbidTransferToContract",
+    AllowMarketTransaction = 44 => "getAllowMarketTransaction",
+    MarketSellFee = 45 => "getMarketSellFee",
+    MarketCancelFee = 46 => "getMarketCancelFee",
+    MaxFeeLimit = 47 => "getMaxFeeLimit",
+    AllowTransactionFeePool = 48 => "getAllowTransactionFeePool",
+    AllowNewResourceModel = 51 => "getAllowNewResourceModel",
+    AllowTvmFreeze = 52 => "getAllowTvmFreeze",
+    AllowTvmVote = 59 => "getAllowTvmVote",
+    FreeNetLimit = 61 => "getFreeNetLimit",
+    TotalNetLimit = 62 => "getTotalNetLimit",
+    AllowNewReward = 67 => "getAllowNewReward",
+    MemoFee = 68 => "getMemoFee",
+    AllowDelegateOptimization = 69 => "getAllowDelegateOptimization",
+    UnfreezeDelayDays = 70 => "getUnfreezeDelayDays",
+    AllowDynamicEnergy = 72 => "getAllowDynamicEnergy",
+    DynamicEnergyThreshold = 73 => "getDynamicEnergyThreshold",
+    DynamicEnergyIncreaseFactor = 74 => "getDynamicEnergyIncreaseFactor",
+    DynamicEnergyMaxFactor = 75 => "getDynamicEnergyMaxFactor",
+    AllowCancelAllUnfreezeV2 = 77 => "getAllowCancelAllUnfreezeV2",
+    MaxDelegateLockPeriod = 78 => "getMaxDelegateLockPeriod",
+    AllowEnergyAdjustment = 81 => "getAllowEnergyAdjustment",
+    MaxCreateAccountTxSize = 82 => "getMaxCreateAccountTxSize",
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalState {
+    #[default]
+    Pending,
+    Disapproved,
+    Approved,
+    Canceled,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proposal {
+    pub proposal_id: i64,
+    pub proposer_address: TronAddress,
+    pub parameters: HashMap<ChainParameter, i64>,
+    pub expiration_time: OffsetDateTime,
+    pub create_time: OffsetDateTime,
+    /// Witnesses approved the proposal
+    pub approvals: Vec<TronAddress>,
+    pub state: ProposalState,
+}
+
+/// Parameter change proposed, see [`Proposal::diff`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProposalChange {
+    pub parameter: ChainParameter,
+    /// `None` if the node doesn't report the parameter
+    pub current: Option<i64>,
+    pub proposed: i64,
+}
+
+impl Proposal {
+    /// Changes against current `chain_parameters()`, sorted by parameter id
+    pub fn diff(
+        &self,
+        chain_parameters: &ChainParameters,
+    ) -> Vec<ProposalChange> {
+        let mut changes: Vec<_> = self
+            .parameters
+            .iter()
+            .map(|(parameter, proposed)| ProposalChange {
+                parameter: *parameter,
+                current: parameter
+                    .key()
+                    .and_then(|key| chain_parameters.get(key)),
+                proposed: *proposed,
+            })
+            .collect();
+        changes.sort_by_key(|c| c.parameter.id());
+        changes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parameter_ids_round_trip() {
+        assert_eq!(ChainParameter::from_id(11), ChainParameter::EnergyFee);
+        assert_eq!(ChainParameter::MemoFee.key(), Some("getMemoFee"));
+        assert_eq!(
+            ChainParameter::from_key("getDynamicEnergyMaxFactor")
+                .map(|p| p.id()),
+            Some(75)
+        );
+        assert_eq!(ChainParameter::from_id(1000).id(), 1000);
+    }
+
+    #[test]
+    fn diff_against_chain_parameters() {
+        let raw = [
+            ("getCreateAccountFee", 100_000),
+            ("getTransactionFee", 1_000),
+            ("getCreateNewAccountFeeInSystemContract", 1_000_000),
+            ("getEnergyFee", 100),
+            ("getAllowMultiSign", 1),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+        let Ok(chain_parameters) = ChainParameters::from_raw(raw) else {
+            panic!("required parameters are set");
+        };
+        let proposal = Proposal {
+            proposal_id: 1,
+            proposer_address: TronAddress::ZERO,
+            parameters: HashMap::from([
+                (ChainParameter::Unknown(1_000), 5),
+                (ChainParameter::EnergyFee, 210),
+                (ChainParameter::AllowMultiSign, 1),
+                (ChainParameter::TransactionFee, 1_000),
+                (ChainParameter::MemoFee, 1_000_000),
+            ]),
+            expiration_time: OffsetDateTime::UNIX_EPOCH,
+            create_time: OffsetDateTime::UNIX_EPOCH,
+            approvals: Vec::new(),
+            state: ProposalState::Pending,
+        };
+        let change = |parameter, current, proposed| ProposalChange {
+            parameter,
+            current,
+            proposed,
+        };
+        assert_eq!(
+            proposal.diff(&chain_parameters),
+            vec![
+                // Unchanged
+                change(ChainParameter::TransactionFee, Some(1_000), 1_000),
+                change(ChainParameter::EnergyFee, Some(100), 210),
+                // Untyped parameter, read from `other`
+                change(ChainParameter::AllowMultiSign, Some(1), 1),
+                // Not reported by the node
+                change(ChainParameter::MemoFee, None, 1_000_000),
+                change(ChainParameter::Unknown(1_000), None, 5),
+            ]
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use time::{Duration, OffsetDateTime};
+
+use super::account::{Account, AccountResourceUsage};
+use super::estimate::Resource;
+
+/// Block producing interval, usage windows are measured in slots
+const SLOT: Duration = Duration::seconds(3);
+/// Default usage window, 24 hours
+const DEFAULT_WINDOW_SLOTS: i64 = 28_800;
+/// Window size multiplier used when window is optimized
+const WINDOW_SIZE_PRECISION: i64 = 1000;
+
+/// Usage of a resource which regenerates linearly over a window,
+/// the same way the node recovers bandwidth and energy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResourceUsage {
+    /// Usage at `latest_consume_time`
+    pub usage: i64,
+    pub latest_consume_time: OffsetDateTime,
+    pub window: Duration,
+    pub limit: i64,
+}
+
+impl ResourceUsage {
+    fn window(window_size: i64, optimized: bool) -> Duration {
+        let slots = if optimized {
+            window_size / WINDOW_SIZE_PRECISION
+        } else {
+            window_size
+        };
+        let slots = if slots > 0 {
+            slots
+        } else {
+            DEFAULT_WINDOW_SLOTS
+        };
+        SLOT * slots as i32
+    }
+    /// Usage remaining at `at`
+    pub fn used_at(&self, at: OffsetDateTime) -> i64 {
+        let elapsed = at - self.latest_consume_time;
+        if elapsed <= Duration::ZERO {
+            return self.usage;
+        }
+        if elapsed >= self.window {
+            return 0;
+        }
+        let window = self.window.whole_milliseconds();
+        let left = window - elapsed.whole_milliseconds();
+        // Rounded like on the node
+        ((self.usage as i128 * left + window / 2) / window) as i64
+    }
+    pub fn available_at(&self, at: OffsetDateTime) -> i64 {
+        (self.limit - self.used_at(at)).max(0)
+    }
+    /// Earliest instant not before `now` when at least `required` is
+    /// available, `None` if the limit is lower than `required`.
+    pub fn available_after(
+        &self,
+        required: i64,
+        now: OffsetDateTime,
+    ) -> Option<OffsetDateTime> {
+        let target = self.limit - required;
+        if target < 0 {
+            return None;
+        }
+        if self.used_at(now) <= target {
+            return Some(now);
+        }
+        // usage * (window - elapsed) / window <= target
+        let window = self.window.whole_milliseconds();
+        let usage = self.usage as i128;
+        let elapsed_ms =
+            (window * (usage - target as i128) + usage - 1) / usage;
+        let at = self.latest_consume_time
+            + Duration::milliseconds(elapsed_ms as i64);
+        Some(at.max(now))
+    }
+}
+
+/// Projects account bandwidth and energy into the future
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResourceProjection {
+    pub free_bandwidth: ResourceUsage,
+    pub bandwidth: ResourceUsage,
+    pub energy: ResourceUsage,
+}
+
+impl ResourceProjection {
+    pub fn new(account: &Account, resources: &AccountResourceUsage) -> Self {
+        let energy = &account.account_resource;
+        ResourceProjection {
+            free_bandwidth: ResourceUsage {
+                usage: account.free_net_usage,
+                latest_consume_time: account.latest_consume_free_time,
+                window: ResourceUsage::window(0, false),
+                limit: resources.free_net_limit,
+            },
+            bandwidth: ResourceUsage {
+                usage: account.net_usage,
+                latest_consume_time: account.latest_consume_time,
+                window: ResourceUsage::window(
+                    account.net_window_size,
+                    account.net_window_optimized,
+                ),
+                limit: resources.net_limit,
+            },
+            energy: ResourceUsage {
+                usage: energy.energy_usage,
+                latest_consume_time: energy.latest_consume_time_for_energy,
+                window: ResourceUsage::window(
+                    energy.energy_window_size,
+                    energy.energy_window_optimized,
+                ),
+                limit: resources.energy_limit,
+            },
+        }
+    }
+    /// Bandwidth and energy available at `at`. Bandwidth is the larger of
+    /// free and staked, they can't be combined in one transaction.
+    pub fn available_at(&self, at: OffsetDateTime) -> Resource {
+        Resource {
+            bandwidth: self
+                .free_bandwidth
+                .available_at(at)
+                .max(self.bandwidth.available_at(at)),
+            energy: self.energy.available_at(at),
+            ..Default::default()
+        }
+    }
+    /// Earliest instant when `required` bandwidth and energy are available
+    /// without burning TRX, `None` if limits are too low. `required.trx`
+    /// is ignored.
+    pub fn when_available(
+        &self,
+        required: Resource,
+        now: OffsetDateTime,
+    ) -> Option<OffsetDateTime> {
+        let bandwidth = match (
+            self.free_bandwidth.available_after(required.bandwidth, now),
+            self.bandwidth.available_after(required.bandwidth, now),
+        ) {
+            (Some(free), Some(staked)) => free.min(staked),
+            (free, staked) => free.or(staked)?,
+        };
+        let energy = self.energy.available_after(required.energy, now)?;
+        Some(bandwidth.max(energy))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn usage_recovers_linearly() {
+        let t0 = OffsetDateTime::UNIX_EPOCH;
+        let usage = ResourceUsage {
+            usage: 1000,
+            latest_consume_time: t0,
+            window: Duration::hours(24),
+            limit: 1000,
+        };
+        assert_eq!(usage.available_at(t0), 0);
+        assert_eq!(usage.available_at(t0 + Duration::hours(6)), 250);
+        assert_eq!(usage.available_at(t0 + Duration::hours(48)), 1000);
+
+        let at = t0 + Duration::hours(12);
+        assert_eq!(usage.available_after(500, t0), Some(at));
+        assert_eq!(usage.available_at(at), 500);
+        assert_eq!(usage.available_after(1001, t0), None);
+    }
+}
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use alloy_primitives::B256;
+use alloy_sol_types::SolEvent;
 use derivative::Derivative;
 use time::OffsetDateTime;
 use time::ext::NumericalDuration;
@@ -100,7 +102,9 @@ pub struct Transaction {
     pub pq_auth_sig: Vec<super::PqAuthSig>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Fields may be added, build it with [`TransactionExtention::new`]
+#[derive(Default, Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct TransactionExtention {
     pub transaction: Option<Transaction>,
     pub txid: Hash32,
@@ -110,6 +114,8 @@ pub struct TransactionExtention {
     pub result: Option<super::Return>,
     pub logs: Vec<Log>,
     pub internal_transactions: Vec<InternalTransaction>,
+    /// Receipt, set by the listener in receipts mode
+    pub info: Option<TransactionInfo>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -210,9 +216,148 @@ impl Transaction {
 }
 
 impl TransactionExtention {
+    /// Transaction without execution results or receipt
+    pub fn new(transaction: Transaction, txid: Hash32) -> Self {
+        TransactionExtention {
+            transaction: Some(transaction),
+            txid,
+            ..Default::default()
+        }
+    }
     pub fn get_contract(&self) -> Option<Contract> {
         self.transaction.as_ref().and_then(|t| t.get_contract())
     }
+    /// Execution outcome, `None` without a receipt
+    pub fn succeeded(&self) -> Option<bool> {
+        self.info.as_ref().map(TransactionInfo::succeeded)
+    }
+    /// Events `E` the constant call emitted
+    pub fn decode_logs<E: SolEvent>(&self) -> Vec<DecodedLog<E>> {
+        decode_logs(&self.logs, None)
+    }
+}
+
+impl TransactionInfo {
+    pub fn succeeded(&self) -> bool {
+        self.result == TxCode::Sucess
+            && self.receipt.as_ref().is_none_or(|r| {
+                matches!(
+                    r.result,
+                    ContractResult::Default | ContractResult::Success
+                )
+            })
+    }
+    /// Events `E` emitted by any contract, e.g.
+    /// `info.decode_logs::<Trc20::Erc20::Transfer>()`
+    pub fn decode_logs<E: SolEvent>(&self) -> Vec<DecodedLog<E>> {
+        decode_logs(&self.log, None)
+    }
+    /// Events `E` emitted by `contract`
+    pub fn decode_logs_from<E: SolEvent>(
+        &self,
+        contract: TronAddress,
+    ) -> Vec<DecodedLog<E>> {
+        decode_logs(&self.log, Some(contract))
+    }
+    /// Logs with their position on chain
+    pub fn log_entries(&self) -> impl Iterator<Item = LogEntry> + '_ {
+        self.log.iter().enumerate().filter_map(|(log_index, log)| {
+            Some(LogEntry {
+                block_number: self.block_number,
+                block_timestamp: self.block_time_stamp,
+                txid: self.id,
+                log_index,
+                address: log.contract_address()?,
+                event: log.clone(),
+            })
+        })
+    }
+}
+
+/// Event decoded from a [`Log`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedLog<E> {
+    /// Emitting contract
+    pub address: TronAddress,
+    pub event: E,
+}
+
+impl Log {
+    /// Emitting contract, logs carry the address without `0x41` prefix
+    pub fn contract_address(&self) -> Option<TronAddress> {
+        if self.address.len() == 20 {
+            Some(alloy_primitives::Address::from_slice(&self.address).into())
+        } else {
+            self.address.as_slice().try_into().ok()
+        }
+    }
+    /// `None` if the log is not `E`
+    pub fn decode<E: SolEvent>(&self) -> Option<DecodedLog<E>> {
+        let topics = self
+            .topics
+            .iter()
+            .map(|t| B256::try_from(t.as_slice()).ok())
+            .collect::<Option<Vec<_>>>()?;
+        let event = E::decode_raw_log(topics, &self.data).ok()?;
+        Some(DecodedLog {
+            address: self.contract_address()?,
+            event,
+        })
+    }
+}
+
+/// Log with its position on chain, see
+/// [`Client::logs`](crate::client::Client::logs)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry<E = Log> {
+    pub block_number: i64,
+    pub block_timestamp: OffsetDateTime,
+    pub txid: Hash32,
+    /// Index of the log in the transaction
+    pub log_index: usize,
+    /// Emitting contract
+    pub address: TronAddress,
+    pub event: E,
+}
+
+impl LogEntry {
+    /// `None` if the log is not `E`
+    pub fn decode<E: SolEvent>(&self) -> Option<LogEntry<E>> {
+        let decoded = self.event.decode::<E>()?;
+        Some(LogEntry {
+            block_number: self.block_number,
+            block_timestamp: self.block_timestamp,
+            txid: self.txid,
+            log_index: self.log_index,
+            address: decoded.address,
+            event: decoded.event,
+        })
+    }
+}
+
+/// Logs of blocks `from_block..=to_block`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogPage {
+    pub from_block: i64,
+    pub to_block: i64,
+    pub logs: Vec<LogEntry>,
+}
+
+impl LogPage {
+    pub fn decode<E: SolEvent>(&self) -> Vec<LogEntry<E>> {
+        self.logs.iter().filter_map(LogEntry::decode::<E>).collect()
+    }
+}
+
+/// Decodes events `E` from `logs`, emitted by `contract` if set
+pub fn decode_logs<E: SolEvent>(
+    logs: &[Log],
+    contract: Option<TronAddress>,
+) -> Vec<DecodedLog<E>> {
+    logs.iter()
+        .filter_map(Log::decode::<E>)
+        .filter(|log| contract.is_none_or(|c| log.address == c))
+        .collect()
 }
 
 impl ContractResult {
@@ -224,3 +369,84 @@ impl ContractResult {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use alloy_primitives::U256;
+
+    use super::*;
+    use crate::contracts::trc20::Trc20::Erc20;
+    use crate::protocol;
+
+    fn succeeded(
+        result: TxCode,
+        contract_result: Option<ContractResult>,
+    ) -> bool {
+        let receipt = contract_result.map(|result| ResourceReceipt {
+            energy_usage: 0,
+            energy_fee: 0,
+            origin_energy_usage: 0,
+            energy_usage_total: 0,
+            net_usage: 0,
+            net_fee: Trx::ZERO,
+            result,
+            energy_penalty_total: 0,
+        });
+        TransactionInfo::try_from(protocol::TransactionInfo::default())
+            .is_ok_and(|info| {
+                TransactionInfo {
+                    result,
+                    receipt,
+                    ..info
+                }
+                .succeeded()
+            })
+    }
+
+    #[test]
+    fn receipt_outcome() {
+        // Transfers and other system contracts carry no contract result
+        assert!(succeeded(TxCode::Sucess, None));
+        assert!(succeeded(TxCode::Sucess, Some(ContractResult::Success)));
+        assert!(succeeded(TxCode::Sucess, Some(ContractResult::Default)));
+        assert!(!succeeded(TxCode::Sucess, Some(ContractResult::Revert)));
+        assert!(!succeeded(
+            TxCode::Sucess,
+            Some(ContractResult::OutOfEnergy)
+        ));
+        assert!(!succeeded(TxCode::Failed, None));
+    }
+
+    #[test]
+    fn decode_transfer_logs() {
+        let token =
+            TronAddress::from(alloy_primitives::Address::repeat_byte(1));
+        let transfer = Erc20::Transfer {
+            from: alloy_primitives::Address::repeat_byte(2),
+            to: alloy_primitives::Address::repeat_byte(3),
+            value: U256::from(100),
+        };
+        let data = transfer.encode_log_data();
+        let log = Log {
+            address: alloy_primitives::Address::from(token).to_vec(),
+            topics: data.topics().iter().map(|t| t.to_vec()).collect(),
+            data: data.data.to_vec(),
+        };
+        let approval = Log {
+            topics: vec![Erc20::Approval::SIGNATURE_HASH.to_vec()],
+            ..log.clone()
+        };
+        let logs = [approval, log];
+
+        let decoded = decode_logs::<Erc20::Transfer>(&logs, Some(token));
+        assert_eq!(decoded.len(), 1);
+        assert!(decoded.iter().all(|d| d.address == token
+            && d.event.value == U256::from(100)
+            && TronAddress::from(d.event.to)
+                == TronAddress::from(transfer.to)));
+        assert!(
+            decode_logs::<Erc20::Transfer>(&logs, Some(TronAddress::ZERO))
+                .is_empty()
+        );
+    }
+}
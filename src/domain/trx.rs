@@ -1,7 +1,9 @@
 use std::fmt;
 
 use derive_more::{Add, AddAssign, Div, Mul, Sub, Sum};
+use serde::{Deserialize, Serialize};
 
+/// Amount in sun, serialized as the number of sun
 #[derive(
     Default,
     Clone,
@@ -16,7 +18,10 @@ use derive_more::{Add, AddAssign, Div, Mul, Sub, Sum};
     Mul,
     Sum,
     Div,
+    Serialize,
+    Deserialize,
 )]
+#[serde(transparent)]
 pub struct Trx(i64);
 
 impl Trx {
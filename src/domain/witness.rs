@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+
+use super::address::TronAddress;
+use super::chain::ChainParameters;
+use super::contract::Vote;
+use super::trx::Trx;
+
+/// Super representative or candidate
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Witness {
+    pub address: TronAddress,
+    pub vote_count: i64,
+    pub pub_key: Vec<u8>,
+    pub url: String,
+    pub total_produced: i64,
+    pub total_missed: i64,
+    pub latest_block_num: i64,
+    pub latest_slot_num: i64,
+    /// Witness is in the active set and produces blocks
+    pub is_jobs: bool,
+}
+
+/// Reward parameters of the network
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WitnessRewardParams {
+    /// Paid to the block producer, `getWitnessPayPerBlock`
+    pub block_reward: Trx,
+    /// Shared among top 127 witnesses by votes, `getWitness127PayPerBlock`
+    pub vote_reward: Trx,
+}
+
+impl WitnessRewardParams {
+    /// Blocks per day
+    pub const BLOCKS_PER_DAY: i64 = 28_800;
+    /// Witnesses producing blocks
+    pub const ACTIVE_WITNESSES: usize = 27;
+    /// Witnesses sharing vote rewards
+    pub const REWARDED_WITNESSES: usize = 127;
+    /// Brokerage of a witness which never changed it, in percent
+    pub const DEFAULT_BROKERAGE: i64 = 20;
+
+    pub fn from_chain_parameters(params: &ChainParameters) -> Self {
+        WitnessRewardParams {
+            block_reward: params.witness_pay_per_block.unwrap_or_default(),
+            vote_reward: params.witness_127_pay_per_block.unwrap_or_default(),
+        }
+    }
+}
+
+/// Expected voter rewards of a witness
+#[derive(Debug, Clone, PartialEq)]
+pub struct WitnessApr {
+    pub witness: Witness,
+    /// Percent of rewards kept by the witness
+    pub brokerage: i64,
+    /// Brokerage is unknown, [`WitnessRewardParams::DEFAULT_BROKERAGE`]
+    /// is assumed
+    pub brokerage_defaulted: bool,
+    /// Whether the witness is in top 27 and produces blocks
+    pub producing: bool,
+    /// Expected yearly voter rewards per vote, in percent (1 vote = 1 TRX)
+    pub apr: f64,
+}
+
+/// Ranks witnesses by expected voter APR, best first.
+///
+/// Rewards are estimated with current votes, the active set is assumed to
+/// stay the same. Witnesses missing from the brokerage map are ranked with
+/// [`WitnessRewardParams::DEFAULT_BROKERAGE`] and marked as defaulted.
+pub struct WitnessRanking {
+    params: WitnessRewardParams,
+    /// Votes of top 127 witnesses
+    total_votes: i64,
+    pub witnesses: Vec<WitnessApr>,
+}
+
+impl WitnessRanking {
+    pub fn new(
+        mut witnesses: Vec<Witness>,
+        brokerage: &HashMap<TronAddress, i64>,
+        params: WitnessRewardParams,
+    ) -> Self {
+        witnesses.sort_by_key(|w| std::cmp::Reverse(w.vote_count));
+        let total_votes = witnesses
+            .iter()
+            .take(WitnessRewardParams::REWARDED_WITNESSES)
+            .map(|w| w.vote_count)
+            .sum();
+
+        let mut ranking = WitnessRanking {
+            params,
+            total_votes,
+            witnesses: Vec::new(),
+        };
+        ranking.witnesses = witnesses
+            .into_iter()
+            .enumerate()
+            .filter(|(rank, _)| *rank < WitnessRewardParams::REWARDED_WITNESSES)
+            .map(|(rank, witness)| {
+                let known = brokerage.get(&witness.address).copied();
+                let brokerage =
+                    known.unwrap_or(WitnessRewardParams::DEFAULT_BROKERAGE);
+                let producing = rank < WitnessRewardParams::ACTIVE_WITNESSES;
+                let apr = ranking.apr(&witness, brokerage, producing, 0);
+                WitnessApr {
+                    witness,
+                    brokerage,
+                    brokerage_defaulted: known.is_none(),
+                    producing,
+                    apr,
+                }
+            })
+            .collect();
+        ranking.witnesses.sort_by(|a, b| b.apr.total_cmp(&a.apr));
+        ranking
+    }
+
+    /// Daily voter rewards in TRX if `extra` votes are added to witness
+    fn voters_daily_reward(
+        &self,
+        witness: &Witness,
+        brokerage: i64,
+        producing: bool,
+        extra: i64,
+    ) -> f64 {
+        let blocks = WitnessRewardParams::BLOCKS_PER_DAY as f64;
+        let votes = (witness.vote_count + extra) as f64;
+        let total_votes = (self.total_votes + extra) as f64;
+        if votes <= 0.0 || total_votes <= 0.0 {
+            return 0.0;
+        }
+
+        let sun_per_trx = 1_000_000.0;
+        let vote_reward = self.params.vote_reward.to_sun() as f64 / sun_per_trx;
+        let block_reward =
+            self.params.block_reward.to_sun() as f64 / sun_per_trx;
+
+        let mut daily = blocks * vote_reward * votes / total_votes;
+        if producing {
+            daily += blocks / WitnessRewardParams::ACTIVE_WITNESSES as f64
+                * block_reward;
+        }
+        daily * (100 - brokerage.clamp(0, 100)) as f64 / 100.0
+    }
+
+    fn apr(
+        &self,
+        witness: &Witness,
+        brokerage: i64,
+        producing: bool,
+        extra: i64,
+    ) -> f64 {
+        let votes = (witness.vote_count + extra) as f64;
+        if votes <= 0.0 {
+            return 0.0;
+        }
+        let daily =
+            self.voters_daily_reward(witness, brokerage, producing, extra);
+        daily / votes * 365.0 * 100.0
+    }
+
+    /// Split `tron_power` votes among at most `max_witnesses` witnesses to
+    /// maximize expected rewards, taking dilution by own votes into account.
+    pub fn propose_votes(
+        &self,
+        tron_power: i64,
+        max_witnesses: usize,
+    ) -> Vec<Vote> {
+        /// Votes are allocated in this many steps
+        const STEPS: i64 = 100;
+
+        let candidates: Vec<&WitnessApr> =
+            self.witnesses.iter().take(max_witnesses.max(1)).collect();
+        if tron_power <= 0 || candidates.is_empty() {
+            return Vec::new();
+        }
+
+        let step = (tron_power / STEPS).max(1);
+        let mut allocated = vec![0i64; candidates.len()];
+        let mut left = tron_power;
+        while left > 0 {
+            let chunk = step.min(left);
+            let gain = |i: usize| {
+                let w = candidates[i];
+                let share = |votes: i64| {
+                    let total = w.witness.vote_count + votes;
+                    if total <= 0 {
+                        return 0.0;
+                    }
+                    self.voters_daily_reward(
+                        &w.witness,
+                        w.brokerage,
+                        w.producing,
+                        votes,
+                    ) * votes as f64
+                        / total as f64
+                };
+                share(allocated[i] + chunk) - share(allocated[i])
+            };
+            let best = (0..candidates.len())
+                .max_by(|a, b| gain(*a).total_cmp(&gain(*b)))
+                .unwrap_or_default();
+            allocated[best] += chunk;
+            left -= chunk;
+        }
+
+        candidates
+            .iter()
+            .zip(allocated)
+            .filter(|(_, votes)| *votes > 0)
+            .map(|(w, vote_count)| Vote {
+                vote_address: w.witness.address,
+                vote_count,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn votes_go_to_best_apr() {
+        let witness = |n: u8, vote_count, brokerage| {
+            let mut bytes = [n; 21];
+            bytes[0] = 0x41;
+            let address =
+                TronAddress::try_from(bytes.as_slice()).unwrap_or_default();
+            (
+                Witness {
+                    address,
+                    vote_count,
+                    ..Default::default()
+                },
+                (address, brokerage),
+            )
+        };
+        let (mut witnesses, brokerage): (Vec<_>, HashMap<_, _>) = [
+            witness(1, 1_000_000_000, 20),
+            witness(2, 1_000_000_000, 100),
+            witness(3, 500_000_000, 20),
+        ]
+        .into_iter()
+        .unzip();
+        // Brokerage unknown
+        witnesses.push(witness(4, 1, 0).0);
+        let ranking = WitnessRanking::new(
+            witnesses,
+            &brokerage,
+            WitnessRewardParams {
+                block_reward: Trx::from_sun(16_000_000),
+                vote_reward: Trx::from_sun(160_000_000),
+            },
+        );
+        let votes = ranking.propose_votes(1_000_000, 2);
+        assert_eq!(votes.iter().map(|v| v.vote_count).sum::<i64>(), 1_000_000);
+        assert!(
+            votes
+                .iter()
+                .all(|v| v.vote_address != ranking.witnesses[3].witness.address)
+        );
+        assert_eq!(ranking.witnesses[3].apr, 0.0);
+        assert!(!ranking.witnesses[3].brokerage_defaulted);
+
+        let defaulted = ranking
+            .witnesses
+            .iter()
+            .filter(|w| w.brokerage_defaulted)
+            .map(|w| (w.witness.vote_count, w.brokerage))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            defaulted,
+            vec![(1, WitnessRewardParams::DEFAULT_BROKERAGE)]
+        );
+    }
+}
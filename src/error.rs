@@ -1,6 +1,7 @@
 use alloy_primitives::U256;
 use time::OffsetDateTime;
 
+use crate::contracts::revert::Revert;
 use crate::domain::address::TronAddress;
 use crate::domain::estimate::ResourceState;
 use crate::domain::transaction::TxCode;
@@ -19,6 +20,8 @@ pub enum Error {
     NoAccount(TronAddress),
     #[error("tron protocol: {0}")]
     TronProtocol(#[from] tonic::Status),
+    #[error("reverted: {0}")]
+    Revert(Revert),
     #[error("transaction failed with: {0}")]
     FailedTransaction(String, Option<ContractResult>),
     #[error("transport error: {0}")]
@@ -4,7 +4,7 @@ use crate::{
         token::{TokenKind, TokenRegistry},
         trc20::Trc20Call,
     },
-    domain::address::TronAddress,
+    domain::{address::TronAddress, contract::TransferAssetContract},
     listener::subscriber::filters::FilterCtx,
 };
 
@@ -43,7 +43,19 @@ impl<R: TokenRegistry> AddressExtractor<FilterCtx<R>>
                 }
             }
             TokenKind::Usdc => None,
+            // TRC-10 transfers are system contracts, see `asset_recipient`
+            TokenKind::Trc10(_) => None,
             TokenKind::Other(_) => None,
         }
     }
 }
+
+/// Recipient of a TRC-10 transfer of an asset known to the registry
+pub fn asset_recipient<R: TokenRegistry>(
+    registry: &R,
+    transfer: &TransferAssetContract,
+) -> Option<TronAddress> {
+    registry
+        .resolve_asset(&transfer.asset_name)
+        .map(|_| transfer.to_address)
+}
@@ -15,6 +15,7 @@ pub mod error;
 pub mod extractor;
 pub mod listener;
 pub mod provider;
+pub mod services;
 pub mod signer;
 pub(crate) mod utility;
 
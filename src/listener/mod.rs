@@ -3,12 +3,13 @@ use futures::{Stream, StreamExt, TryStreamExt};
 use std::collections::VecDeque;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 use tokio::time::{Duration, sleep};
 
 use crate::Result;
 use crate::client::Client;
+use crate::domain::Hash32;
 use crate::domain::block::BlockExtention;
 use crate::listener::block_cache::{BlockCache, InMemoryBlockCache};
 use crate::provider::TronProvider;
@@ -18,6 +19,9 @@ pub mod block_cache;
 pub mod subscriber;
 
 const MAX_BLOCKS_PER_FETCH: i64 = 100;
+/// Recent blocks kept to verify parent hashes, deeper forks are not
+/// detected. Blocks are irreversible after 19 confirmations.
+const REORG_WINDOW: usize = 64;
 
 #[derive(Clone, Debug)]
 pub struct ListenerError(Arc<crate::error::Error>);
@@ -51,9 +55,27 @@ impl std::error::Error for ListenerError {
 pub type ListenerMsg =
     std::result::Result<crate::domain::block::BlockExtention, ListenerError>;
 
+/// Blocks `from..=to` were replaced by a fork. Canonical blocks are
+/// delivered again right after.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reorg {
+    pub from: i64,
+    pub to: i64,
+    /// Ids of the replaced blocks, by number
+    pub orphaned: Vec<Hash32>,
+}
+
+// Blocks are the common case, boxing them would only add an allocation
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+pub enum ListenerEvent {
+    Block(ListenerMsg),
+    Reorg(Reorg),
+}
+
 pub struct ListenerHandle {
     shutdown: Option<tokio::sync::oneshot::Sender<()>>,
-    rx: tokio::sync::broadcast::Receiver<ListenerMsg>,
+    rx: tokio::sync::broadcast::Receiver<ListenerEvent>,
 }
 
 impl ListenerHandle {
@@ -66,7 +88,12 @@ impl ListenerHandle {
         tokio::spawn(async move {
             loop {
                 match rx.recv().await {
-                    Ok(msg) => subscriber.handle(msg).await,
+                    Ok(ListenerEvent::Block(msg)) => {
+                        subscriber.handle(msg).await
+                    }
+                    Ok(ListenerEvent::Reorg(reorg)) => {
+                        subscriber.on_reorg(reorg).await
+                    }
                     Err(tokio::sync::broadcast::error::RecvError::Lagged(
                         n,
                     )) => {
@@ -90,10 +117,26 @@ impl Drop for ListenerHandle {
     }
 }
 
+/// Which blocks the listener delivers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Confirmation {
+    /// Up to the head block, may still be reverted by a reorg
+    #[default]
+    Head,
+    /// Up to the latest solidified, irreversible block. Read from the
+    /// full node's `last_solidity_block_num`, the block a solidity node
+    /// serves as its now block.
+    Solidified,
+    /// Up to the given number of blocks behind head
+    Depth(i64),
+}
+
 pub struct Listener<P, S, BC = InMemoryBlockCache> {
     client: Client<P, S>,
     block_cache: BC,
     interval: Duration,
+    with_receipts: bool,
+    confirmation: Confirmation,
 }
 
 impl<P, S> Listener<P, S, InMemoryBlockCache>
@@ -107,6 +150,8 @@ where
             client,
             block_cache: InMemoryBlockCache::default(),
             interval: block_poll_interval,
+            with_receipts: false,
+            confirmation: Confirmation::Head,
         }
     }
 }
@@ -127,6 +172,8 @@ where
             client,
             block_cache,
             interval: block_poll_interval,
+            with_receipts: false,
+            confirmation: Confirmation::Head,
         }
     }
 
@@ -138,19 +185,36 @@ where
             client: self.client,
             block_cache,
             interval: self.interval,
+            with_receipts: self.with_receipts,
+            confirmation: self.confirmation,
         }
     }
 
+    /// Pair every transaction with its receipt, see
+    /// [`TransactionExtention::info`](crate::domain::transaction::TransactionExtention::info).
+    /// Costs one more request per block.
+    pub fn with_receipts(mut self) -> Self {
+        self.with_receipts = true;
+        self
+    }
+
+    /// Deliver only blocks that reached `confirmation`, so subscribers
+    /// never see reversible data
+    pub fn with_confirmation(mut self, confirmation: Confirmation) -> Self {
+        self.confirmation = confirmation;
+        self
+    }
+
     pub async fn run(self) -> ListenerHandle {
         let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
         let (tx, rx) = tokio::sync::broadcast::channel(128);
 
-        let mut block_stream = self.block_stream();
+        let mut event_stream = self.event_stream();
 
         tokio::spawn(async move {
             loop {
                 tokio::select! {
-                    Some(msg) = block_stream.next() => {
+                    Some(msg) = event_stream.next() => {
                         if let Err(e) = tx.send(msg) {
                             tracing::error!("failed to send block msg: {e}");
                         }
@@ -169,23 +233,203 @@ where
         }
     }
 
-    pub(crate) fn block_stream(self) -> impl Stream<Item = ListenerMsg> {
+    pub(crate) fn event_stream(self) -> impl Stream<Item = ListenerEvent> {
         BlockStream {
             listener: self,
             delay: Box::pin(sleep(Duration::from_secs(0))),
             fut: None,
-            pending_blocks: VecDeque::new(),
+            pending_events: VecDeque::new(),
+            recent: Default::default(),
         }
     }
+
+    /// Blocks only, canonical blocks after a reorg are still delivered
+    pub(crate) fn block_stream(self) -> impl Stream<Item = ListenerMsg> {
+        self.event_stream().filter_map(|event| {
+            futures::future::ready(match event {
+                ListenerEvent::Block(msg) => Some(msg),
+                ListenerEvent::Reorg(_) => None,
+            })
+        })
+    }
+}
+
+/// Highest block number to deliver, with the block itself if it is the
+/// head
+async fn confirmed_height<P, S>(
+    client: &Client<P, S>,
+    confirmation: Confirmation,
+) -> Result<(Option<BlockExtention>, i64)>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+{
+    Ok(match confirmation {
+        Confirmation::Head => {
+            let block = client.provider().get_now_block().await?;
+            let number = block.block_header.raw_data.number;
+            (Some(block), number)
+        }
+        Confirmation::Solidified => {
+            let properties = client.provider().get_dynamic_properties().await?;
+            (None, properties.last_solidity_block_num)
+        }
+        Confirmation::Depth(depth) => {
+            let block = client.provider().get_now_block().await?;
+            let number = block.block_header.raw_data.number;
+            if depth <= 0 {
+                (Some(block), number)
+            } else {
+                (None, number - depth)
+            }
+        }
+    })
+}
+
+/// Ids of recently delivered blocks, by number
+type RecentBlocks = Arc<Mutex<VecDeque<(i64, Hash32)>>>;
+
+fn remember(recent: &RecentBlocks, block: &BlockExtention) {
+    let mut recent = recent.lock().unwrap_or_else(|e| e.into_inner());
+    recent.push_back((block.block_header.raw_data.number, block.blockid));
+    if recent.len() > REORG_WINDOW {
+        recent.pop_front();
+    }
+}
+
+/// Whether `block` doesn't extend the last delivered block
+fn forks(recent: &RecentBlocks, block: &BlockExtention) -> bool {
+    let raw = &block.block_header.raw_data;
+    recent
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .back()
+        .is_some_and(|(number, id)| {
+            *number == raw.number - 1 && *id != raw.parent_hash
+        })
+}
+
+/// Verifies parent hashes of new blocks, emitting a reorg and the
+/// canonical blocks when a block doesn't extend the last delivered one.
+/// Stops at a block that is itself off the canonical chain, it is fetched
+/// again on the next poll.
+async fn chain_blocks<F, Fut>(
+    fetch: F,
+    recent: &RecentBlocks,
+    blocks: Vec<BlockExtention>,
+) -> Result<Vec<ListenerEvent>>
+where
+    F: Fn(i64) -> Fut,
+    Fut: Future<Output = Result<BlockExtention>>,
+{
+    let mut events = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        if forks(recent, &block) {
+            let number = block.block_header.raw_data.number;
+            let (reorg, canonical) =
+                find_fork(&fetch, recent, number - 1).await?;
+            if let Some(reorg) = reorg {
+                tracing::warn!(?reorg, "chain reorganization");
+                events.push(ListenerEvent::Reorg(reorg));
+            }
+            for block in canonical {
+                remember(recent, &block);
+                events.push(ListenerEvent::Block(Ok(block)));
+            }
+            if forks(recent, &block) {
+                tracing::warn!(number, "block is off the canonical chain");
+                break;
+            }
+        }
+        remember(recent, &block);
+        events.push(ListenerEvent::Block(Ok(block)));
+    }
+    Ok(events)
+}
+
+/// Walks back from `tip` to the last block both chains share
+async fn find_fork<F, Fut>(
+    fetch: F,
+    recent: &RecentBlocks,
+    tip: i64,
+) -> Result<(Option<Reorg>, Vec<BlockExtention>)>
+where
+    F: Fn(i64) -> Fut,
+    Fut: Future<Output = Result<BlockExtention>>,
+{
+    let seen = |number: i64| {
+        recent
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|(n, _)| *n == number)
+            .map(|(_, id)| *id)
+    };
+
+    let mut number = tip;
+    let mut orphaned = Vec::new();
+    let mut canonical = Vec::new();
+    while let Some(id) = seen(number) {
+        let block = fetch(number).await?;
+        if block.blockid == id {
+            break;
+        }
+        orphaned.push(id);
+        canonical.push(block);
+        number -= 1;
+    }
+    recent
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|(n, _)| *n <= number);
+
+    orphaned.reverse();
+    canonical.reverse();
+    // Nothing replaced, the new block itself is off the canonical chain
+    let reorg = (!orphaned.is_empty()).then_some(Reorg {
+        from: number + 1,
+        to: tip,
+        orphaned,
+    });
+    Ok((reorg, canonical))
+}
+
+async fn attach_receipts<P, S>(
+    client: &Client<P, S>,
+    events: Vec<ListenerEvent>,
+) -> Result<Vec<ListenerEvent>>
+where
+    P: TronProvider,
+    S: PrehashSigner,
+{
+    events
+        .into_iter()
+        .map(|event| async move {
+            let ListenerEvent::Block(Ok(mut block)) = event else {
+                return Ok(event);
+            };
+            let infos = client
+                .provider()
+                .get_transaction_info_by_block_num(
+                    block.block_header.raw_data.number,
+                )
+                .await?;
+            block.attach_receipts(infos);
+            Ok(ListenerEvent::Block(Ok(block)))
+        })
+        .collect::<FuturesOrdered<_>>()
+        .try_collect()
+        .await
 }
 
 struct BlockStream<P, S, BC> {
     listener: Listener<P, S, BC>,
     delay: Pin<Box<tokio::time::Sleep>>,
     fut: Option<
-        Pin<Box<dyn Future<Output = Result<Vec<BlockExtention>>> + Send>>,
+        Pin<Box<dyn Future<Output = Result<Vec<ListenerEvent>>> + Send>>,
     >,
-    pending_blocks: VecDeque<BlockExtention>,
+    pending_events: VecDeque<ListenerEvent>,
+    recent: RecentBlocks,
 }
 
 impl<P, S, BC> Unpin for BlockStream<P, S, BC> {}
@@ -197,14 +441,14 @@ where
     S::Error: std::fmt::Debug,
     BC: BlockCache + Clone,
 {
-    type Item = ListenerMsg;
+    type Item = ListenerEvent;
 
     fn poll_next(
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        if let Some(block) = self.pending_blocks.pop_front() {
-            return Poll::Ready(Some(Ok(block)));
+        if let Some(event) = self.pending_events.pop_front() {
+            return Poll::Ready(Some(event));
         }
 
         if self.delay.as_mut().poll(cx).is_pending() {
@@ -214,6 +458,9 @@ where
         if self.fut.is_none() {
             let client = self.listener.client.clone();
             let block_cache = self.listener.block_cache.clone();
+            let with_receipts = self.listener.with_receipts;
+            let confirmation = self.listener.confirmation;
+            let recent = self.recent.clone();
 
             self.fut = Some(Box::pin(async move {
                 let last_block =
@@ -223,11 +470,20 @@ where
                         Err(e) => return Err(e.into()),
                     };
 
-                let latest_block = client.provider.get_now_block().await?;
-                let latest_number = latest_block.block_header.raw_data.number;
+                let (latest_block, latest_number) =
+                    confirmed_height(&client, confirmation).await?;
 
                 let blocks_to_send = if last_block == -1 {
-                    vec![latest_block]
+                    match latest_block {
+                        Some(block) => vec![block],
+                        None if latest_number < 0 => Vec::new(),
+                        None => vec![
+                            client
+                                .provider()
+                                .get_block_by_number(latest_number)
+                                .await?,
+                        ],
+                    }
                 } else if latest_number <= last_block {
                     Vec::new()
                 } else {
@@ -249,17 +505,32 @@ where
                     futures.try_collect().await?
                 };
 
-                if let Some(last_block) = blocks_to_send.last() {
-                    let block_number = last_block.block_header.raw_data.number;
+                let fetch =
+                    |number| client.provider().get_block_by_number(number);
+                let events =
+                    chain_blocks(fetch, &recent, blocks_to_send).await?;
+                // Blocks after one off the canonical chain are not delivered
+                let last_number =
+                    events.iter().rev().find_map(|event| match event {
+                        ListenerEvent::Block(Ok(block)) => {
+                            Some(block.block_header.raw_data.number)
+                        }
+                        _ => None,
+                    });
+                let events = if with_receipts {
+                    attach_receipts(&client, events).await?
+                } else {
+                    events
+                };
 
-                    if let Err(e) =
+                if let Some(block_number) = last_number
+                    && let Err(e) =
                         block_cache.store_latest_seen_block(block_number).await
-                    {
-                        return Err(e.into());
-                    }
+                {
+                    return Err(e.into());
                 }
 
-                Ok(blocks_to_send)
+                Ok(events)
             }));
         }
 
@@ -271,24 +542,24 @@ where
         };
 
         match poll_result {
-            Poll::Ready(Ok(blocks)) => {
+            Poll::Ready(Ok(events)) => {
                 self.fut = None;
                 self.delay
                     .as_mut()
                     .reset(tokio::time::Instant::now() + interval);
 
-                if blocks.is_empty() {
+                if events.is_empty() {
                     return Poll::Pending;
                 }
 
-                self.pending_blocks.extend(blocks);
+                self.pending_events.extend(events);
 
-                let block = self
-                    .pending_blocks
+                let event = self
+                    .pending_events
                     .pop_front()
-                    .expect("pending blocks must not be empty");
+                    .expect("pending events must not be empty");
 
-                Poll::Ready(Some(Ok(block)))
+                Poll::Ready(Some(event))
             }
             Poll::Ready(Err(e)) => {
                 self.fut = None;
@@ -296,9 +567,258 @@ where
                     .as_mut()
                     .reset(tokio::time::Instant::now() + interval);
 
-                Poll::Ready(Some(Err(ListenerError::from(e))))
+                Poll::Ready(Some(ListenerEvent::Block(Err(
+                    ListenerError::from(e),
+                ))))
             }
             Poll::Pending => Poll::Pending,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use futures::future::{Ready, ready};
+    use time::OffsetDateTime;
+
+    use super::*;
+    use crate::domain::address::TronAddress;
+    use crate::domain::block::{BlockHeader, RawBlockHeader};
+    use crate::domain::chain::DynamicProperties;
+    use crate::provider::mock::MockProvider;
+    use crate::signer::LocalSigner;
+
+    /// Block of a chain whose blocks from `fork` on were replaced
+    fn block(number: i64, fork: i64) -> BlockExtention {
+        let id = |n: i64| {
+            let byte = if n >= fork { n + 100 } else { n };
+            Hash32::from([byte as u8; 32])
+        };
+        BlockExtention {
+            transactions: Vec::new(),
+            block_header: BlockHeader {
+                raw_data: RawBlockHeader {
+                    timestamp: OffsetDateTime::UNIX_EPOCH,
+                    tx_trie_root: Hash32::default(),
+                    parent_hash: id(number - 1),
+                    number,
+                    witness_id: 0,
+                    witness_address: TronAddress::default(),
+                    version: 0,
+                    account_state_root: Hash32::default(),
+                },
+                witness_signature: None,
+                pq_auth_sig: None,
+            },
+            blockid: id(number),
+        }
+    }
+
+    /// Node serving the chain forked at `fork`
+    fn node(fork: i64) -> impl Fn(i64) -> Ready<Result<BlockExtention>> {
+        move |number| ready(Ok(block(number, fork)))
+    }
+
+    /// Blocks `1..=tip` delivered before the fork
+    fn delivered(tip: i64) -> RecentBlocks {
+        let recent = RecentBlocks::default();
+        for number in 1..=tip {
+            remember(&recent, &block(number, i64::MAX));
+        }
+        recent
+    }
+
+    /// Reorgs and block numbers, in order
+    fn summary(events: Vec<ListenerEvent>) -> (Vec<Reorg>, Vec<i64>) {
+        let mut reorgs = Vec::new();
+        let mut blocks = Vec::new();
+        for event in events {
+            match event {
+                ListenerEvent::Reorg(reorg) => reorgs.push(reorg),
+                ListenerEvent::Block(Ok(block)) => {
+                    blocks.push(block.block_header.raw_data.number)
+                }
+                ListenerEvent::Block(Err(e)) => panic!("{e}"),
+            }
+        }
+        (reorgs, blocks)
+    }
+
+    fn ids(numbers: std::ops::RangeInclusive<i64>) -> Vec<Hash32> {
+        numbers.map(|n| block(n, i64::MAX).blockid).collect()
+    }
+
+    #[tokio::test]
+    async fn one_block_fork() -> Result<()> {
+        let recent = delivered(10);
+        let events = chain_blocks(node(10), &recent, vec![block(11, 10)]);
+        let (reorgs, blocks) = summary(events.await?);
+
+        assert_eq!(
+            reorgs,
+            [Reorg {
+                from: 10,
+                to: 10,
+                orphaned: ids(10..=10),
+            }]
+        );
+        assert_eq!(blocks, [10, 11]);
+        assert!(!forks(&recent, &block(12, 10)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn deep_fork() -> Result<()> {
+        let recent = delivered(10);
+        let new_blocks = vec![block(11, 5), block(12, 5)];
+        let events = chain_blocks(node(5), &recent, new_blocks);
+        let (reorgs, blocks) = summary(events.await?);
+
+        assert_eq!(
+            reorgs,
+            [Reorg {
+                from: 5,
+                to: 10,
+                orphaned: ids(5..=10),
+            }]
+        );
+        assert_eq!(blocks, [5, 6, 7, 8, 9, 10, 11, 12]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fork_deeper_than_window() -> Result<()> {
+        let tip = REORG_WINDOW as i64 + 10;
+        let recent = delivered(tip);
+        let events = chain_blocks(node(1), &recent, vec![block(tip + 1, 1)]);
+        let (reorgs, blocks) = summary(events.await?);
+
+        // Only the remembered blocks are reported
+        let from = tip - REORG_WINDOW as i64 + 1;
+        assert_eq!(
+            reorgs,
+            [Reorg {
+                from,
+                to: tip,
+                orphaned: ids(from..=tip),
+            }]
+        );
+        assert_eq!(blocks, (from..=tip + 1).collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stale_block_is_not_delivered() -> Result<()> {
+        let recent = delivered(10);
+        // Fetched from a node still on a fork the chain abandoned
+        let stale = vec![block(11, 10), block(12, 10)];
+        let events = chain_blocks(node(i64::MAX), &recent, stale);
+        let (reorgs, blocks) = summary(events.await?);
+
+        assert!(reorgs.is_empty() && blocks.is_empty());
+        assert!(!forks(&recent, &block(11, i64::MAX)));
+        Ok(())
+    }
+
+    /// Node serving blocks `1..=tip`
+    fn extend(provider: &MockProvider, tip: i64) {
+        provider.update(|s| {
+            for number in 1..=tip {
+                s.blocks.insert(number, block(number, i64::MAX));
+            }
+        });
+    }
+
+    fn solidify(provider: &MockProvider, last_solidity_block_num: i64) {
+        provider.update(|s| {
+            s.dynamic_properties = Some(DynamicProperties {
+                last_solidity_block_num,
+            })
+        });
+    }
+
+    /// Block numbers delivered until the stream goes quiet
+    async fn next_blocks(
+        stream: &mut (impl Stream<Item = ListenerEvent> + Unpin),
+    ) -> Vec<i64> {
+        let mut numbers = Vec::new();
+        let quiet = Duration::from_millis(200);
+        while let Ok(Some(event)) =
+            tokio::time::timeout(quiet, stream.next()).await
+        {
+            match event {
+                ListenerEvent::Block(Ok(block)) => {
+                    numbers.push(block.block_header.raw_data.number)
+                }
+                event => panic!("{event:?}"),
+            }
+        }
+        numbers
+    }
+
+    /// Blocks delivered at start, and once `moved` changes the chain
+    /// while the listener is waiting
+    async fn confirmed(
+        provider: MockProvider,
+        confirmation: Confirmation,
+        moved: impl FnOnce(&MockProvider),
+    ) -> (Vec<i64>, Vec<i64>) {
+        let client = Client::<_, LocalSigner>::builder()
+            .provider(provider.clone())
+            .build();
+        let listener = Listener::new(client, Duration::from_millis(1))
+            .with_confirmation(confirmation);
+        let mut stream = Box::pin(listener.event_stream());
+
+        let first = next_blocks(&mut stream).await;
+        let (later, ()) = tokio::join!(next_blocks(&mut stream), async {
+            sleep(Duration::from_millis(20)).await;
+            moved(&provider);
+        });
+        (first, later)
+    }
+
+    #[tokio::test]
+    async fn solidified_blocks_are_delivered() {
+        let provider = MockProvider::new().await;
+        extend(&provider, 10);
+        solidify(&provider, 5);
+
+        let (first, later) =
+            confirmed(provider, Confirmation::Solidified, |provider| {
+                solidify(provider, 8)
+            })
+            .await;
+        assert_eq!(first, [5]);
+        assert_eq!(later, [6, 7, 8]);
+    }
+
+    #[tokio::test]
+    async fn blocks_are_delivered_at_depth() {
+        let provider = MockProvider::new().await;
+        extend(&provider, 10);
+
+        let (first, later) =
+            confirmed(provider, Confirmation::Depth(3), |provider| {
+                extend(provider, 12)
+            })
+            .await;
+        // 8..=10 are held back until 3 blocks are built on top
+        assert_eq!(first, [7]);
+        assert_eq!(later, [8, 9]);
+    }
+
+    #[tokio::test]
+    async fn zero_depth_is_head() {
+        let provider = MockProvider::new().await;
+        extend(&provider, 10);
+
+        let (first, later) =
+            confirmed(provider, Confirmation::Depth(0), |provider| {
+                extend(provider, 12)
+            })
+            .await;
+        assert_eq!(first, [10]);
+        assert_eq!(later, [11, 12]);
+    }
+}
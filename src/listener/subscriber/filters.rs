@@ -5,9 +5,9 @@ use crate::Filter;
 use crate::contracts::token::{InMemoryTokenRegistry, TokenRegistry};
 use crate::domain::address::TronAddress;
 use crate::domain::block::BlockExtention;
-use crate::domain::contract::{Contract, TriggerSmartContract};
+use crate::domain::contract::{Contract, ContractType, TriggerSmartContract};
 use crate::domain::transaction::TransactionExtention;
-use crate::extractor::AddressExtractor;
+use crate::extractor::{AddressExtractor, asset_recipient};
 
 #[derive(Clone)]
 pub struct AddressFilter<F, R, E> {
@@ -71,6 +71,8 @@ where
         block
             .transactions
             .into_iter()
+            // Failed transactions moved nothing, known in receipts mode
+            .filter(|tx| tx.succeeded() != Some(false))
             .filter(|tx| {
                 contains_addr::<R, E>(tx, &addrs, &self.token_registry)
             })
@@ -92,8 +94,13 @@ where
         let check_owner = |contract: &Contract| {
             contract.owner_address().is_some_and(|a| addrs.contains(&a))
         };
-        let check_to = |contract: &Contract| {
-            contract.to_address().is_some_and(|a| addrs.contains(&a))
+        let check_to = |contract: &Contract| match &contract.contract_type {
+            // Anyone can send worthless assets, count only known ones
+            ContractType::TransferAssetContract(transfer) => {
+                asset_recipient(registry, transfer)
+                    .is_some_and(|a| addrs.contains(&a))
+            }
+            _ => contract.to_address().is_some_and(|a| addrs.contains(&a)),
         };
         let check_contract = |contract: &Contract| {
             contract
@@ -115,3 +122,151 @@ where
         false
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use alloy_primitives::U256;
+    use alloy_sol_types::SolCall;
+    use time::OffsetDateTime;
+
+    use super::*;
+    use crate::contracts::token::TokenKind;
+    use crate::contracts::trc20::Trc20::Erc20;
+    use crate::domain::Hash32;
+    use crate::domain::block::{BlockHeader, RawBlockHeader};
+    use crate::domain::contract::TransferAssetContract;
+    use crate::domain::transaction::{Transaction, TransactionInfo, TxCode};
+    use crate::extractor::DynamicTrc20Extractor;
+    use crate::protocol;
+
+    fn address(n: u8) -> TronAddress {
+        let mut bytes = [n; 21];
+        bytes[0] = 0x41;
+        TronAddress::try_from(bytes.as_slice()).unwrap_or_default()
+    }
+
+    fn tx(contract_type: ContractType, txid: u8) -> TransactionExtention {
+        let mut transaction = Transaction::default();
+        transaction.raw.contract.push(Contract {
+            contract_type,
+            ..Default::default()
+        });
+        TransactionExtention {
+            transaction: Some(transaction),
+            txid: Hash32::from([txid; 32]),
+            constant_result: Vec::new(),
+            energy_used: 0,
+            energy_penalty: 0,
+            result: None,
+            logs: Vec::new(),
+            internal_transactions: Vec::new(),
+            info: None,
+        }
+    }
+
+    fn asset_transfer(asset_id: &str, to: TronAddress) -> TransactionExtention {
+        tx(
+            ContractType::TransferAssetContract(TransferAssetContract {
+                asset_name: asset_id.into(),
+                owner_address: address(1),
+                to_address: to,
+                amount: Default::default(),
+            }),
+            0,
+        )
+    }
+
+    fn usdt_transfer(
+        to: TronAddress,
+        txid: u8,
+        result: Option<TxCode>,
+    ) -> TransactionExtention {
+        let data = Erc20::transferCall {
+            recipient: to.into(),
+            amount: U256::from(1),
+        }
+        .abi_encode();
+        let mut tx = tx(
+            ContractType::TriggerSmartContract(TriggerSmartContract {
+                owner_address: address(1),
+                contract_address: address(9),
+                data: data.into(),
+                ..Default::default()
+            }),
+            txid,
+        );
+        tx.info = result.and_then(|result| {
+            let info = protocol::TransactionInfo::default();
+            let info = TransactionInfo::try_from(info).ok()?;
+            Some(TransactionInfo { result, ..info })
+        });
+        tx
+    }
+
+    #[test]
+    fn only_known_assets_match_recipient() {
+        let registry =
+            InMemoryTokenRegistry::default().with_assets(HashMap::from([(
+                "1002000".to_string(),
+                TokenKind::Trc10("1002000".into()),
+            )]));
+        let recipient = HashSet::from([address(2)]);
+        let sender = HashSet::from([address(1)]);
+        let matches = |tx: &TransactionExtention, addrs| {
+            contains_addr::<_, ()>(tx, addrs, &registry)
+        };
+
+        let known = asset_transfer("1002000", address(2));
+        let unknown = asset_transfer("1000001", address(2));
+        assert!(matches(&known, &recipient));
+        assert!(!matches(&unknown, &recipient));
+        // Outgoing transfers match whatever the asset
+        assert!(matches(&unknown, &sender));
+    }
+
+    #[tokio::test]
+    async fn failed_transfers_are_skipped_with_receipts() {
+        let registry = InMemoryTokenRegistry::from(HashMap::from([(
+            address(9),
+            TokenKind::Usdt,
+        )]));
+        let filter =
+            AddressFilter::new(|| async { HashSet::from([address(2)]) })
+                .with_extractor::<DynamicTrc20Extractor>()
+                .with_registry(registry);
+        let block = BlockExtention {
+            transactions: vec![
+                usdt_transfer(address(2), 1, Some(TxCode::Sucess)),
+                usdt_transfer(address(2), 2, Some(TxCode::Failed)),
+                // Without receipts the outcome is unknown
+                usdt_transfer(address(2), 3, None),
+                usdt_transfer(address(3), 4, Some(TxCode::Sucess)),
+            ],
+            block_header: BlockHeader {
+                raw_data: RawBlockHeader {
+                    timestamp: OffsetDateTime::UNIX_EPOCH,
+                    tx_trie_root: Hash32::default(),
+                    parent_hash: Hash32::default(),
+                    number: 1,
+                    witness_id: 0,
+                    witness_address: TronAddress::default(),
+                    version: 0,
+                    account_state_root: Hash32::default(),
+                },
+                witness_signature: None,
+                pq_auth_sig: None,
+            },
+            blockid: Hash32::default(),
+        };
+
+        let txids: Vec<_> = filter
+            .filter(block)
+            .await
+            .into_iter()
+            .map(|tx| tx.txid)
+            .collect();
+        assert_eq!(txids, [Hash32::from([1; 32]), Hash32::from([3; 32])]);
+    }
+}
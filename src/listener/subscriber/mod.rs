@@ -1,14 +1,18 @@
 use std::future::Future;
 
-use crate::listener::ListenerMsg;
+use crate::listener::{ListenerMsg, Reorg};
 
 pub mod filters;
 pub mod tx_sub;
+pub mod witness_monitor;
 
 // TODO: implement for fnonce
 #[async_trait::async_trait]
 pub trait BlockSubscriber {
     async fn handle(&self, msg: ListenerMsg);
+    /// Called before the canonical blocks of a fork are delivered again,
+    /// effects of the orphaned blocks should be undone here
+    async fn on_reorg(&self, _reorg: Reorg) {}
 }
 
 #[async_trait::async_trait]
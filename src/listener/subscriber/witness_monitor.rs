@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use crate::domain::address::TronAddress;
+use crate::domain::block::RawBlockHeader;
+use crate::listener::{ListenerError, ListenerMsg};
+
+use super::BlockSubscriber;
+
+/// One block is produced every slot
+const SLOT: time::Duration = time::Duration::seconds(3);
+
+/// Block production of a witness since the monitor started
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WitnessStats {
+    pub produced: u64,
+    pub missed: u64,
+    /// Number of the last block produced
+    pub latest_block_num: i64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WitnessEvent {
+    Produced {
+        witness: TronAddress,
+        block_num: i64,
+    },
+    /// Slot before `block_num` was skipped. `witness` is `None` until the
+    /// production order around the slot is observed.
+    Missed {
+        witness: Option<TronAddress>,
+        block_num: i64,
+    },
+}
+
+#[derive(Default)]
+struct State {
+    stats: HashMap<TronAddress, WitnessStats>,
+    /// Witness producing right after the key witness, learned from
+    /// consecutive blocks. Changes each maintenance period.
+    next: HashMap<TronAddress, TronAddress>,
+    last: Option<RawBlockHeader>,
+    unattributed_missed: u64,
+}
+
+/// Reports produced and missed blocks per witness. Missed slots are
+/// detected from gaps between block timestamps and attributed with
+/// the production order seen so far.
+///
+/// Cheap to clone, clones share the stats.
+#[derive(Clone)]
+pub struct WitnessMonitor<H> {
+    state: Arc<Mutex<State>>,
+    handler: H,
+}
+
+impl<H, Fut> WitnessMonitor<H>
+where
+    H: Fn(Result<WitnessEvent, ListenerError>) -> Fut + Send + Sync + Clone,
+    Fut: Future<Output = ()> + Send,
+{
+    pub fn new(handler: H) -> Self {
+        Self {
+            state: Default::default(),
+            handler,
+        }
+    }
+}
+
+impl<H> WitnessMonitor<H> {
+    pub fn stats(&self) -> HashMap<TronAddress, WitnessStats> {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .stats
+            .clone()
+    }
+    /// Missed slots not attributed to any witness
+    pub fn unattributed_missed(&self) -> u64 {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .unattributed_missed
+    }
+    fn observe(&self, header: RawBlockHeader) -> Vec<WitnessEvent> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut events = Vec::new();
+
+        if let Some(last) = state.last.take() {
+            if header.number <= last.number {
+                // Already seen or reorged, keep the newest view
+                state.last = Some(header);
+                return events;
+            }
+            // Slots between blocks not seen by the listener are not counted
+            let skipped = if header.number == last.number + 1 {
+                ((header.timestamp - last.timestamp).whole_seconds()
+                    / SLOT.whole_seconds()
+                    - 1)
+                .max(0)
+            } else {
+                0
+            };
+            if skipped == 0 && header.number == last.number + 1 {
+                state
+                    .next
+                    .insert(last.witness_address, header.witness_address);
+            }
+            let mut previous = Some(last.witness_address);
+            for _ in 0..skipped {
+                let witness =
+                    previous.and_then(|p| state.next.get(&p).copied());
+                match witness {
+                    Some(w) => {
+                        state.stats.entry(w).or_default().missed += 1;
+                    }
+                    None => state.unattributed_missed += 1,
+                }
+                events.push(WitnessEvent::Missed {
+                    witness,
+                    block_num: header.number,
+                });
+                previous = witness;
+            }
+        }
+
+        let stats = state.stats.entry(header.witness_address).or_default();
+        stats.produced += 1;
+        stats.latest_block_num = header.number;
+        events.push(WitnessEvent::Produced {
+            witness: header.witness_address,
+            block_num: header.number,
+        });
+        state.last = Some(header);
+        events
+    }
+}
+
+#[async_trait::async_trait]
+impl<H, Fut> BlockSubscriber for WitnessMonitor<H>
+where
+    H: Fn(Result<WitnessEvent, ListenerError>) -> Fut + Send + Sync + Clone,
+    Fut: Future<Output = ()> + Send,
+{
+    async fn handle(&self, msg: ListenerMsg) {
+        let block = match msg {
+            Ok(be) => be,
+            Err(e) => {
+                (self.handler.clone())(Err(e)).await;
+                return;
+            }
+        };
+        for event in self.observe(block.block_header.raw_data) {
+            (self.handler.clone())(Ok(event)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::Hash32;
+
+    fn witness(n: u8) -> TronAddress {
+        let mut bytes = [n; 21];
+        bytes[0] = 0x41;
+        TronAddress::try_from(bytes.as_slice()).unwrap_or_default()
+    }
+
+    fn header(number: i64, slot: i64, witness_address: u8) -> RawBlockHeader {
+        RawBlockHeader {
+            timestamp: time::OffsetDateTime::UNIX_EPOCH
+                + time::Duration::seconds(SLOT.whole_seconds() * slot),
+            tx_trie_root: Hash32::default(),
+            parent_hash: Hash32::default(),
+            number,
+            witness_id: 0,
+            witness_address: witness(witness_address),
+            version: 0,
+            account_state_root: Hash32::default(),
+        }
+    }
+
+    #[test]
+    fn missed_slots_are_attributed_by_order() {
+        let monitor = WitnessMonitor::new(|_| async {});
+        // Production order 1 -> 2 -> 3 -> 1
+        for (number, w) in [(1, 1), (2, 2), (3, 3), (4, 1)] {
+            assert_eq!(
+                monitor.observe(header(number, number, w)),
+                vec![WitnessEvent::Produced {
+                    witness: witness(w),
+                    block_num: number,
+                }]
+            );
+        }
+
+        // Witness 2 skipped its slot
+        assert_eq!(
+            monitor.observe(header(5, 6, 3)),
+            vec![
+                WitnessEvent::Missed {
+                    witness: Some(witness(2)),
+                    block_num: 5,
+                },
+                WitnessEvent::Produced {
+                    witness: witness(3),
+                    block_num: 5,
+                },
+            ]
+        );
+        let stats = monitor.stats();
+        assert_eq!(
+            stats.get(&witness(2)),
+            Some(&WitnessStats {
+                produced: 1,
+                missed: 1,
+                latest_block_num: 2,
+            })
+        );
+        assert_eq!(stats.get(&witness(1)).map(|s| s.produced), Some(2));
+        assert_eq!(monitor.unattributed_missed(), 0);
+    }
+
+    #[test]
+    fn unknown_successor_is_unattributed() {
+        let monitor = WitnessMonitor::new(|_| async {});
+        monitor.observe(header(1, 1, 1));
+        // Two slots after witness 1 skipped, order not known yet
+        let events = monitor.observe(header(2, 4, 2));
+        assert_eq!(
+            events[..2],
+            [
+                WitnessEvent::Missed {
+                    witness: None,
+                    block_num: 2,
+                },
+                WitnessEvent::Missed {
+                    witness: None,
+                    block_num: 2,
+                },
+            ]
+        );
+        assert_eq!(monitor.unattributed_missed(), 2);
+        // Gap in block numbers, slots are not counted
+        assert_eq!(monitor.observe(header(4, 10, 3)).len(), 1);
+        assert_eq!(monitor.unattributed_missed(), 2);
+    }
+}
@@ -1229,6 +1229,21 @@ impl From<ContractState> for domain::contract::ContractState {
     }
 }
 
+impl From<SmartContractDataWrapper>
+    for domain::contract::SmartContractDataWrapper
+{
+    fn from(value: SmartContractDataWrapper) -> Self {
+        domain::contract::SmartContractDataWrapper {
+            smart_contract: value
+                .smart_contract
+                .filter(|c| !c.origin_address.is_empty())
+                .map(Into::into),
+            runtime_code: value.runtimecode,
+            contract_state: value.contract_state.map(Into::into),
+        }
+    }
+}
+
 impl From<CreateSmartContract> for domain::contract::CreateSmartContract {
     fn from(value: CreateSmartContract) -> Self {
         domain::contract::CreateSmartContract {
@@ -389,6 +389,7 @@ impl TryFrom<TransactionExtention>
                 .into_iter()
                 .map(Into::into)
                 .collect(),
+            info: None,
         })
     }
 }
@@ -402,8 +403,12 @@ impl From<domain::transaction::TransactionExtention> for TransactionExtention {
             energy_used: txext.energy_used,
             energy_penalty: txext.energy_penalty,
             result: txext.result.map(Into::into),
-            logs: todo!(),
-            internal_transactions: todo!(),
+            logs: txext.logs.into_iter().map(Into::into).collect(),
+            internal_transactions: txext
+                .internal_transactions
+                .into_iter()
+                .map(Into::into)
+                .collect(),
         }
     }
 }
@@ -1104,6 +1109,154 @@ impl TryFrom<DelegatedResource> for domain::account::DelegatedResource {
     }
 }
 
+impl TryFrom<Exchange> for domain::exchange::Exchange {
+    type Error = ProtoConvError;
+    fn try_from(value: Exchange) -> Result<Self, Self::Error> {
+        Ok(domain::exchange::Exchange {
+            exchange_id: value.exchange_id,
+            creator_address: value
+                .creator_address
+                .as_slice()
+                .try_into()
+                .unwrap_or_default(),
+            create_time: OffsetDateTime::try_from_tron(value.create_time)?,
+            first_token_id: String::from_utf8_lossy(&value.first_token_id)
+                .into(),
+            first_token_balance: value.first_token_balance,
+            second_token_id: String::from_utf8_lossy(&value.second_token_id)
+                .into(),
+            second_token_balance: value.second_token_balance,
+        })
+    }
+}
+
+impl_enum_conversions! {
+    market_order::State => domain::market::MarketOrderState {
+        Active,
+        Inactive,
+        Canceled
+    }
+}
+
+impl TryFrom<MarketOrder> for domain::market::MarketOrder {
+    type Error = ProtoConvError;
+    fn try_from(value: MarketOrder) -> Result<Self, Self::Error> {
+        Ok(domain::market::MarketOrder {
+            order_id: value.order_id.as_slice().try_into().unwrap_or_default(),
+            owner_address: value
+                .owner_address
+                .as_slice()
+                .try_into()
+                .unwrap_or_default(),
+            create_time: OffsetDateTime::try_from_tron(value.create_time)?,
+            state: value.state().into(),
+            sell_token_id: String::from_utf8_lossy(&value.sell_token_id).into(),
+            sell_token_quantity: value.sell_token_quantity,
+            buy_token_id: String::from_utf8_lossy(&value.buy_token_id).into(),
+            buy_token_quantity: value.buy_token_quantity,
+            sell_token_quantity_remain: value.sell_token_quantity_remain,
+            sell_token_quantity_return: value.sell_token_quantity_return,
+        })
+    }
+}
+
+impl From<MarketOrderPair> for domain::market::MarketPair {
+    fn from(value: MarketOrderPair) -> Self {
+        domain::market::MarketPair {
+            sell_token_id: String::from_utf8_lossy(&value.sell_token_id).into(),
+            buy_token_id: String::from_utf8_lossy(&value.buy_token_id).into(),
+        }
+    }
+}
+
+impl From<domain::market::MarketPair> for MarketOrderPair {
+    fn from(value: domain::market::MarketPair) -> Self {
+        MarketOrderPair {
+            sell_token_id: value.sell_token_id.into_bytes(),
+            buy_token_id: value.buy_token_id.into_bytes(),
+        }
+    }
+}
+
+impl From<MarketPrice> for domain::market::MarketPrice {
+    fn from(value: MarketPrice) -> Self {
+        domain::market::MarketPrice {
+            sell_token_quantity: value.sell_token_quantity,
+            buy_token_quantity: value.buy_token_quantity,
+        }
+    }
+}
+
+impl TryFrom<ChainParameters> for domain::chain::ChainParameters {
+    type Error = ProtoConvError;
+    fn try_from(value: ChainParameters) -> Result<Self, Self::Error> {
+        domain::chain::ChainParameters::from_raw(
+            value
+                .chain_parameter
+                .into_iter()
+                .map(|p| (p.key, p.value))
+                .collect(),
+        )
+        .map_err(ProtoConvError::Missing)
+    }
+}
+
+impl_enum_conversions! {
+    proposal::State => domain::proposal::ProposalState {
+        Pending,
+        Disapproved,
+        Approved,
+        Canceled,
+    }
+}
+
+impl TryFrom<Proposal> for domain::proposal::Proposal {
+    type Error = ProtoConvError;
+    fn try_from(value: Proposal) -> Result<Self, Self::Error> {
+        Ok(domain::proposal::Proposal {
+            proposal_id: value.proposal_id,
+            proposer_address: value
+                .proposer_address
+                .as_slice()
+                .try_into()
+                .unwrap_or_default(),
+            parameters: value
+                .parameters
+                .iter()
+                .map(|(id, v)| {
+                    (domain::proposal::ChainParameter::from_id(*id), *v)
+                })
+                .collect(),
+            expiration_time: OffsetDateTime::try_from_tron(
+                value.expiration_time,
+            )?,
+            create_time: OffsetDateTime::try_from_tron(value.create_time)?,
+            approvals: value
+                .approvals
+                .iter()
+                .map(|a| a.as_slice().try_into().unwrap_or_default())
+                .collect(),
+            state: value.state().into(),
+        })
+    }
+}
+
+impl From<Witness> for domain::witness::Witness {
+    fn from(value: Witness) -> Self {
+        domain::witness::Witness {
+            address: value.address.as_slice().try_into().unwrap_or_default(),
+            vote_count: value.vote_count,
+            pub_key: value.pub_key,
+            url: value.url,
+            total_produced: value.total_produced,
+            total_missed: value.total_missed,
+            latest_block_num: value.latest_block_num,
+            latest_slot_num: value.latest_slot_num,
+            is_jobs: value.is_jobs,
+        }
+    }
+}
+
 impl From<domain::account::DelegatedResource> for DelegatedResource {
     fn from(value: domain::account::DelegatedResource) -> Self {
         DelegatedResource {
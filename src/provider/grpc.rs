@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::future::Future;
 use std::time::Duration;
 
@@ -14,8 +13,10 @@ use crate::domain::trx::{self, Trx};
 use crate::domain::{self, Hash32};
 use crate::error::Error;
 use crate::protocol;
+use crate::protocol::database_client::DatabaseClient;
 use crate::protocol::wallet_client::WalletClient;
 use crate::provider::grpc::middleware::auth_channel;
+use crate::utility::TronOffsetDateTime;
 
 #[derive(Clone, Builder)]
 #[builder(finish_fn(vis = "", name = build_internal))]
@@ -112,6 +113,9 @@ impl GrpcProvider {
     fn wallet_client(&self) -> WalletClient<middleware::AuthChannel> {
         WalletClient::new(self.channel.clone())
     }
+    fn database_client(&self) -> DatabaseClient<middleware::AuthChannel> {
+        DatabaseClient::new(self.channel.clone())
+    }
     fn return_to_result(ret: Option<protocol::Return>) -> Result<()> {
         if let Some(protocol::Return {
             result: false,
@@ -439,7 +443,32 @@ impl crate::provider::TronProvider for GrpcProvider {
         Ok(transaction.try_into()?)
     }
 
-    async fn chain_parameters(&self) -> Result<HashMap<String, i64>> {
+    async fn get_transaction_info_by_block_num(
+        &self,
+        block_num: i64,
+    ) -> Result<Vec<domain::transaction::TransactionInfo>> {
+        let response = self
+            .retry_grpc("get_transaction_info_by_block_num", || {
+                let mut node = self.wallet_client();
+
+                async move {
+                    node.get_transaction_info_by_block_num(
+                        protocol::NumberMessage { num: block_num },
+                    )
+                    .await
+                }
+            })
+            .await?
+            .into_inner();
+
+        Ok(response
+            .transaction_info
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<std::result::Result<_, _>>()?)
+    }
+
+    async fn chain_parameters(&self) -> Result<domain::chain::ChainParameters> {
         let chain_parameters = self
             .retry_grpc("chain_parameters", || {
                 let mut node = self.wallet_client();
@@ -450,13 +479,69 @@ impl crate::provider::TronProvider for GrpcProvider {
                 }
             })
             .await?
-            .into_inner()
-            .chain_parameter
-            .into_iter()
-            .map(|ch_p| (ch_p.key, ch_p.value))
-            .collect::<HashMap<_, _>>();
+            .into_inner();
+
+        Ok(chain_parameters.try_into()?)
+    }
+
+    async fn get_dynamic_properties(
+        &self,
+    ) -> Result<domain::chain::DynamicProperties> {
+        let properties = self
+            .retry_grpc("get_dynamic_properties", || {
+                let mut node = self.database_client();
+
+                async move {
+                    node.get_dynamic_properties(
+                        protocol::EmptyMessage::default(),
+                    )
+                    .await
+                }
+            })
+            .await?
+            .into_inner();
+
+        Ok(domain::chain::DynamicProperties {
+            last_solidity_block_num: properties.last_solidity_block_num,
+        })
+    }
+
+    async fn get_energy_prices(&self) -> Result<domain::chain::PriceHistory> {
+        let prices = self
+            .retry_grpc("get_energy_prices", || {
+                let mut node = self.wallet_client();
+
+                async move {
+                    node.get_energy_prices(protocol::EmptyMessage::default())
+                        .await
+                }
+            })
+            .await?
+            .into_inner();
+
+        domain::chain::PriceHistory::parse(&prices.prices).ok_or_else(|| {
+            eyre!("invalid energy prices: {}", prices.prices).into()
+        })
+    }
+
+    async fn get_bandwidth_prices(
+        &self,
+    ) -> Result<domain::chain::PriceHistory> {
+        let prices = self
+            .retry_grpc("get_bandwidth_prices", || {
+                let mut node = self.wallet_client();
+
+                async move {
+                    node.get_bandwidth_prices(protocol::EmptyMessage::default())
+                        .await
+                }
+            })
+            .await?
+            .into_inner();
 
-        Ok(chain_parameters)
+        domain::chain::PriceHistory::parse(&prices.prices).ok_or_else(|| {
+            eyre!("invalid bandwidth prices: {}", prices.prices).into()
+        })
     }
 
     async fn freeze_balance(
@@ -566,6 +651,439 @@ impl crate::provider::TronProvider for GrpcProvider {
 
         Ok(index.try_into()?)
     }
+
+    async fn get_contract_info(
+        &self,
+        contract_address: TronAddress,
+    ) -> Result<domain::contract::SmartContractDataWrapper> {
+        let message = protocol::BytesMessage {
+            value: contract_address.as_bytes().to_vec(),
+        };
+
+        let info = self
+            .retry_grpc("get_contract_info", || {
+                let mut node = self.wallet_client();
+                let message = message.clone();
+
+                async move { node.get_contract_info(message).await }
+            })
+            .await?
+            .into_inner();
+
+        Ok(info.into())
+    }
+
+    async fn get_available_unfreeze_count(
+        &self,
+        owner: TronAddress,
+    ) -> Result<i64> {
+        let message = protocol::GetAvailableUnfreezeCountRequestMessage {
+            owner_address: owner.as_bytes().to_vec(),
+        };
+
+        let response = self
+            .retry_grpc("get_available_unfreeze_count", || {
+                let mut node = self.wallet_client();
+                let message = message.clone();
+
+                async move { node.get_available_unfreeze_count(message).await }
+            })
+            .await?
+            .into_inner();
+
+        Ok(response.count)
+    }
+
+    async fn get_can_withdraw_unfreeze_amount(
+        &self,
+        owner: TronAddress,
+        timestamp: time::OffsetDateTime,
+    ) -> Result<Trx> {
+        let message = protocol::CanWithdrawUnfreezeAmountRequestMessage {
+            owner_address: owner.as_bytes().to_vec(),
+            timestamp: timestamp.to_tron(),
+        };
+
+        let response = self
+            .retry_grpc("get_can_withdraw_unfreeze_amount", || {
+                let mut node = self.wallet_client();
+                let message = message.clone();
+
+                async move {
+                    node.get_can_withdraw_unfreeze_amount(message).await
+                }
+            })
+            .await?
+            .into_inner();
+
+        Ok(response.amount.into())
+    }
+
+    async fn get_can_delegated_max_size(
+        &self,
+        owner: TronAddress,
+        resource: domain::contract::ResourceCode,
+    ) -> Result<Trx> {
+        let message = protocol::CanDelegatedMaxSizeRequestMessage {
+            r#type: protocol::ResourceCode::from(resource) as i32,
+            owner_address: owner.as_bytes().to_vec(),
+            ..Default::default()
+        };
+
+        let response = self
+            .retry_grpc("get_can_delegated_max_size", || {
+                let mut node = self.wallet_client();
+                let message = message.clone();
+
+                async move { node.get_can_delegated_max_size(message).await }
+            })
+            .await?
+            .into_inner();
+
+        Ok(response.max_size.into())
+    }
+
+    async fn list_witnesses(&self) -> Result<Vec<domain::witness::Witness>> {
+        let response = self
+            .retry_grpc("list_witnesses", || {
+                let mut node = self.wallet_client();
+
+                async move {
+                    node.list_witnesses(protocol::EmptyMessage::default()).await
+                }
+            })
+            .await?
+            .into_inner();
+
+        Ok(response.witnesses.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_paginated_now_witness_list(
+        &self,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<domain::witness::Witness>> {
+        let message = protocol::PaginatedMessage { offset, limit };
+
+        let response = self
+            .retry_grpc("get_paginated_now_witness_list", || {
+                let mut node = self.wallet_client();
+
+                async move { node.get_paginated_now_witness_list(message).await }
+            })
+            .await?
+            .into_inner();
+
+        Ok(response.witnesses.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_brokerage_info(&self, witness: TronAddress) -> Result<i64> {
+        let message = protocol::BytesMessage {
+            value: witness.as_bytes().to_vec(),
+        };
+
+        let response = self
+            .retry_grpc("get_brokerage_info", || {
+                let mut node = self.wallet_client();
+                let message = message.clone();
+
+                async move { node.get_brokerage_info(message).await }
+            })
+            .await?
+            .into_inner();
+
+        Ok(response.num)
+    }
+
+    async fn get_next_maintenance_time(&self) -> Result<time::OffsetDateTime> {
+        let response = self
+            .retry_grpc("get_next_maintenance_time", || {
+                let mut node = self.wallet_client();
+
+                async move {
+                    node.get_next_maintenance_time(
+                        protocol::EmptyMessage::default(),
+                    )
+                    .await
+                }
+            })
+            .await?
+            .into_inner();
+
+        Ok(time::OffsetDateTime::try_from_tron(response.num)
+            .map_err(protocol::ProtoConvError::from)?)
+    }
+
+    async fn get_asset_issue_by_id(
+        &self,
+        id: &str,
+    ) -> Result<domain::contract::AssetIssueContract> {
+        let message = protocol::BytesMessage {
+            value: id.as_bytes().to_vec(),
+        };
+
+        let asset = self
+            .retry_grpc("get_asset_issue_by_id", || {
+                let mut node = self.wallet_client();
+                let message = message.clone();
+
+                async move { node.get_asset_issue_by_id(message).await }
+            })
+            .await?
+            .into_inner();
+
+        // Node returns an empty asset if not found
+        if asset.id.is_empty() {
+            return Err(Error::NotFound(format!("asset {id} not found")));
+        }
+        Ok(asset.into())
+    }
+
+    async fn get_asset_issue_by_name(
+        &self,
+        name: &str,
+    ) -> Result<domain::contract::AssetIssueContract> {
+        let message = protocol::BytesMessage {
+            value: name.as_bytes().to_vec(),
+        };
+
+        let asset = self
+            .retry_grpc("get_asset_issue_by_name", || {
+                let mut node = self.wallet_client();
+                let message = message.clone();
+
+                async move { node.get_asset_issue_by_name(message).await }
+            })
+            .await?
+            .into_inner();
+
+        if asset.id.is_empty() {
+            return Err(Error::NotFound(format!("asset {name} not found")));
+        }
+        Ok(asset.into())
+    }
+
+    async fn get_asset_issue_list(
+        &self,
+    ) -> Result<Vec<domain::contract::AssetIssueContract>> {
+        let response = self
+            .retry_grpc("get_asset_issue_list", || {
+                let mut node = self.wallet_client();
+
+                async move {
+                    node.get_asset_issue_list(protocol::EmptyMessage::default())
+                        .await
+                }
+            })
+            .await?
+            .into_inner();
+
+        Ok(response.asset_issue.into_iter().map(Into::into).collect())
+    }
+
+    async fn list_exchanges(&self) -> Result<Vec<domain::exchange::Exchange>> {
+        let response = self
+            .retry_grpc("list_exchanges", || {
+                let mut node = self.wallet_client();
+
+                async move {
+                    node.list_exchanges(protocol::EmptyMessage::default()).await
+                }
+            })
+            .await?
+            .into_inner();
+
+        Ok(response
+            .exchanges
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<std::result::Result<_, _>>()?)
+    }
+
+    async fn get_exchange_by_id(
+        &self,
+        exchange_id: i64,
+    ) -> Result<domain::exchange::Exchange> {
+        let message = protocol::BytesMessage {
+            value: exchange_id.to_be_bytes().to_vec(),
+        };
+
+        let response = self
+            .retry_grpc("get_exchange_by_id", || {
+                let mut node = self.wallet_client();
+                let message = message.clone();
+
+                async move { node.get_exchange_by_id(message).await }
+            })
+            .await?
+            .into_inner();
+
+        // Node returns an empty exchange if not found
+        if response.exchange_id != exchange_id {
+            return Err(Error::NotFound(format!(
+                "exchange {exchange_id} not found"
+            )));
+        }
+        Ok(response.try_into()?)
+    }
+
+    async fn get_market_order_by_account(
+        &self,
+        address: TronAddress,
+    ) -> Result<Vec<domain::market::MarketOrder>> {
+        let message = protocol::BytesMessage {
+            value: address.as_bytes().to_vec(),
+        };
+
+        let response = self
+            .retry_grpc("get_market_order_by_account", || {
+                let mut node = self.wallet_client();
+                let message = message.clone();
+
+                async move { node.get_market_order_by_account(message).await }
+            })
+            .await?
+            .into_inner();
+
+        Ok(response
+            .orders
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<std::result::Result<_, _>>()?)
+    }
+
+    async fn get_market_order_by_id(
+        &self,
+        order_id: Hash32,
+    ) -> Result<domain::market::MarketOrder> {
+        let message = protocol::BytesMessage {
+            value: order_id.into(),
+        };
+
+        let response = self
+            .retry_grpc("get_market_order_by_id", || {
+                let mut node = self.wallet_client();
+                let message = message.clone();
+
+                async move { node.get_market_order_by_id(message).await }
+            })
+            .await?
+            .into_inner();
+
+        // Node returns an empty order if not found
+        if response.order_id.is_empty() {
+            return Err(Error::NotFound(format!(
+                "market order {order_id:?} not found"
+            )));
+        }
+        Ok(response.try_into()?)
+    }
+
+    async fn get_market_pair_list(
+        &self,
+    ) -> Result<Vec<domain::market::MarketPair>> {
+        let response = self
+            .retry_grpc("get_market_pair_list", || {
+                let mut node = self.wallet_client();
+
+                async move {
+                    node.get_market_pair_list(protocol::EmptyMessage::default())
+                        .await
+                }
+            })
+            .await?
+            .into_inner();
+
+        Ok(response.order_pair.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_market_price_by_pair(
+        &self,
+        pair: domain::market::MarketPair,
+    ) -> Result<Vec<domain::market::MarketPrice>> {
+        let message = protocol::MarketOrderPair::from(pair);
+
+        let response = self
+            .retry_grpc("get_market_price_by_pair", || {
+                let mut node = self.wallet_client();
+                let message = message.clone();
+
+                async move { node.get_market_price_by_pair(message).await }
+            })
+            .await?
+            .into_inner();
+
+        Ok(response.prices.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_market_order_list_by_pair(
+        &self,
+        pair: domain::market::MarketPair,
+    ) -> Result<Vec<domain::market::MarketOrder>> {
+        let message = protocol::MarketOrderPair::from(pair);
+
+        let response = self
+            .retry_grpc("get_market_order_list_by_pair", || {
+                let mut node = self.wallet_client();
+                let message = message.clone();
+
+                async move { node.get_market_order_list_by_pair(message).await }
+            })
+            .await?
+            .into_inner();
+
+        Ok(response
+            .orders
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<std::result::Result<_, _>>()?)
+    }
+
+    async fn list_proposals(&self) -> Result<Vec<domain::proposal::Proposal>> {
+        let response = self
+            .retry_grpc("list_proposals", || {
+                let mut node = self.wallet_client();
+
+                async move {
+                    node.list_proposals(protocol::EmptyMessage::default()).await
+                }
+            })
+            .await?
+            .into_inner();
+
+        Ok(response
+            .proposals
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<std::result::Result<_, _>>()?)
+    }
+
+    async fn get_proposal_by_id(
+        &self,
+        proposal_id: i64,
+    ) -> Result<domain::proposal::Proposal> {
+        let message = protocol::BytesMessage {
+            value: proposal_id.to_be_bytes().to_vec(),
+        };
+
+        let response = self
+            .retry_grpc("get_proposal_by_id", || {
+                let mut node = self.wallet_client();
+                let message = message.clone();
+
+                async move { node.get_proposal_by_id(message).await }
+            })
+            .await?
+            .into_inner();
+
+        // Node returns an empty proposal if not found
+        if response.proposal_id != proposal_id {
+            return Err(Error::NotFound(format!(
+                "proposal {proposal_id} not found"
+            )));
+        }
+        Ok(response.try_into()?)
+    }
 }
 
 pub mod middleware {
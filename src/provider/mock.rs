@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
 
 use eyre::eyre;
 
@@ -9,12 +10,49 @@ use crate::domain::trx::{self, Trx};
 use crate::domain::{self, Hash32};
 use crate::error::Error;
 
-#[derive(Clone, Copy, Debug)]
-pub struct MockProvider;
+/// Responses served by [`MockProvider`], a call whose response isn't set
+/// fails with an error
+#[derive(Default, Debug, Clone)]
+pub struct MockState {
+    /// Blocks by number, the last one is the now block
+    pub blocks: BTreeMap<i64, domain::block::BlockExtention>,
+    pub chain_parameters: Option<domain::chain::ChainParameters>,
+    pub dynamic_properties: Option<domain::chain::DynamicProperties>,
+    pub accounts: HashMap<TronAddress, domain::account::Account>,
+    pub account_resources:
+        HashMap<TronAddress, domain::account::AccountResourceUsage>,
+    pub delegated_resources: Vec<domain::account::DelegatedResource>,
+    /// Transactions broadcasted so far
+    pub broadcasts: Vec<domain::transaction::Transaction>,
+    pub estimate_energy: Option<i64>,
+    pub constant_call: Option<domain::transaction::TransactionExtention>,
+    pub contracts:
+        HashMap<TronAddress, domain::contract::SmartContractDataWrapper>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MockProvider {
+    state: Arc<Mutex<MockState>>,
+}
 
 impl MockProvider {
     pub async fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Change the responses, shared by all clones of the provider
+    pub fn update(&self, f: impl FnOnce(&mut MockState)) {
+        f(&mut self.state.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Read the current responses
+    pub fn state<T>(&self, f: impl FnOnce(&MockState) -> T) -> T {
+        f(&self.state.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    fn respond<T>(&self, f: impl FnOnce(&MockState) -> Option<T>) -> Result<T> {
+        self.state(f)
+            .ok_or_else(|| Error::Unexpected(eyre!("mock provider")))
     }
 }
 
@@ -38,36 +76,37 @@ impl crate::provider::TronProvider for MockProvider {
     }
     async fn broadcast_transaction(
         &self,
-        _: domain::transaction::Transaction,
+        transaction: domain::transaction::Transaction,
     ) -> Result<()> {
-        Err(Error::Unexpected(eyre!("mock provider")))
+        self.update(|s| s.broadcasts.push(transaction));
+        Ok(())
     }
     async fn estimate_energy(
         &self,
         _: domain::contract::TriggerSmartContract,
     ) -> Result<i64> {
-        Err(Error::Unexpected(eyre!("mock provider")))
+        self.respond(|s| s.estimate_energy)
     }
     async fn get_account(
         &self,
-        _: TronAddress,
+        address: TronAddress,
     ) -> Result<domain::account::Account> {
-        Err(Error::Unexpected(eyre!("mock provider")))
+        self.respond(|s| s.accounts.get(&address).cloned())
     }
     async fn get_account_resources(
         &self,
-        _: TronAddress,
+        address: TronAddress,
     ) -> Result<domain::account::AccountResourceUsage> {
-        Err(Error::Unexpected(eyre!("mock provider")))
+        self.respond(|s| s.account_resources.get(&address).cloned())
     }
     async fn trigger_constant_contract(
         &self,
         _: domain::contract::TriggerSmartContract,
     ) -> Result<domain::transaction::TransactionExtention> {
-        Err(Error::Unexpected(eyre!("mock provider")))
+        self.respond(|s| s.constant_call.clone())
     }
     async fn get_now_block(&self) -> Result<domain::block::BlockExtention> {
-        Err(Error::Unexpected(eyre!("mock provider")))
+        self.respond(|s| s.blocks.values().last().cloned())
     }
     async fn account_permission_update(
         &self,
@@ -87,7 +126,26 @@ impl crate::provider::TronProvider for MockProvider {
     ) -> Result<domain::transaction::TransactionInfo> {
         Err(Error::Unexpected(eyre!("mock provider")))
     }
-    async fn chain_parameters(&self) -> Result<HashMap<String, i64>> {
+    async fn get_transaction_info_by_block_num(
+        &self,
+        _: i64,
+    ) -> Result<Vec<domain::transaction::TransactionInfo>> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn chain_parameters(&self) -> Result<domain::chain::ChainParameters> {
+        self.respond(|s| s.chain_parameters.clone())
+    }
+    async fn get_dynamic_properties(
+        &self,
+    ) -> Result<domain::chain::DynamicProperties> {
+        self.respond(|s| s.dynamic_properties)
+    }
+    async fn get_energy_prices(&self) -> Result<domain::chain::PriceHistory> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_bandwidth_prices(
+        &self,
+    ) -> Result<domain::chain::PriceHistory> {
         Err(Error::Unexpected(eyre!("mock provider")))
     }
     async fn freeze_balance(
@@ -107,21 +165,150 @@ impl crate::provider::TronProvider for MockProvider {
     }
     async fn get_delegated_resource(
         &self,
-        _: TronAddress,
-        _: TronAddress,
+        from: TronAddress,
+        to: TronAddress,
     ) -> Result<Vec<domain::account::DelegatedResource>> {
-        Err(Error::Unexpected(eyre!("mock provider")))
+        Ok(self.state(|s| {
+            s.delegated_resources
+                .iter()
+                .filter(|r| r.from == from && r.to == to)
+                .cloned()
+                .collect()
+        }))
     }
     async fn get_delegated_resource_account(
         &self,
-        _: TronAddress,
+        address: TronAddress,
     ) -> Result<domain::account::DelegatedResourceAccountIndex> {
-        Err(Error::Unexpected(eyre!("mock provider")))
+        Ok(
+            self.state(|s| domain::account::DelegatedResourceAccountIndex {
+                account: address,
+                from_accounts: s
+                    .delegated_resources
+                    .iter()
+                    .filter(|r| r.to == address)
+                    .map(|r| r.from)
+                    .collect(),
+                to_accounts: s
+                    .delegated_resources
+                    .iter()
+                    .filter(|r| r.from == address)
+                    .map(|r| r.to)
+                    .collect(),
+                ..Default::default()
+            }),
+        )
     }
     async fn get_block_by_number(
         &self,
-        _block_num: i64,
+        block_num: i64,
     ) -> Result<domain::block::BlockExtention> {
+        self.respond(|s| s.blocks.get(&block_num).cloned())
+    }
+    async fn get_contract_info(
+        &self,
+        address: TronAddress,
+    ) -> Result<domain::contract::SmartContractDataWrapper> {
+        self.respond(|s| s.contracts.get(&address).cloned())
+    }
+    async fn get_available_unfreeze_count(
+        &self,
+        _: TronAddress,
+    ) -> Result<i64> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_can_withdraw_unfreeze_amount(
+        &self,
+        _: TronAddress,
+        _: time::OffsetDateTime,
+    ) -> Result<Trx> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_can_delegated_max_size(
+        &self,
+        _: TronAddress,
+        _: domain::contract::ResourceCode,
+    ) -> Result<Trx> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn list_witnesses(&self) -> Result<Vec<domain::witness::Witness>> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_paginated_now_witness_list(
+        &self,
+        _: i64,
+        _: i64,
+    ) -> Result<Vec<domain::witness::Witness>> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_brokerage_info(&self, _: TronAddress) -> Result<i64> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_next_maintenance_time(&self) -> Result<time::OffsetDateTime> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_asset_issue_by_id(
+        &self,
+        _: &str,
+    ) -> Result<domain::contract::AssetIssueContract> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_asset_issue_by_name(
+        &self,
+        _: &str,
+    ) -> Result<domain::contract::AssetIssueContract> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_asset_issue_list(
+        &self,
+    ) -> Result<Vec<domain::contract::AssetIssueContract>> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn list_exchanges(&self) -> Result<Vec<domain::exchange::Exchange>> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_exchange_by_id(
+        &self,
+        _: i64,
+    ) -> Result<domain::exchange::Exchange> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_market_order_by_account(
+        &self,
+        _: TronAddress,
+    ) -> Result<Vec<domain::market::MarketOrder>> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_market_order_by_id(
+        &self,
+        _: Hash32,
+    ) -> Result<domain::market::MarketOrder> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_market_pair_list(
+        &self,
+    ) -> Result<Vec<domain::market::MarketPair>> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_market_price_by_pair(
+        &self,
+        _: domain::market::MarketPair,
+    ) -> Result<Vec<domain::market::MarketPrice>> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_market_order_list_by_pair(
+        &self,
+        _: domain::market::MarketPair,
+    ) -> Result<Vec<domain::market::MarketOrder>> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn list_proposals(&self) -> Result<Vec<domain::proposal::Proposal>> {
+        Err(Error::Unexpected(eyre!("mock provider")))
+    }
+    async fn get_proposal_by_id(
+        &self,
+        _: i64,
+    ) -> Result<domain::proposal::Proposal> {
         Err(Error::Unexpected(eyre!("mock provider")))
     }
 }
@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::time::Duration;
 
 use crate::Result;
@@ -8,7 +7,7 @@ use crate::domain::trx::Trx;
 use crate::domain::{self, Hash32};
 
 pub mod grpc;
-#[cfg(feature = "mock-provider")]
+#[cfg(any(test, feature = "mock-provider"))]
 pub mod mock;
 
 #[derive(Clone)]
@@ -68,7 +67,20 @@ pub trait TronProvider {
         &self,
         txid: Hash32,
     ) -> Result<domain::transaction::TransactionInfo>;
-    async fn chain_parameters(&self) -> Result<HashMap<String, i64>>;
+    /// Receipts of all transactions in the block
+    async fn get_transaction_info_by_block_num(
+        &self,
+        block_num: i64,
+    ) -> Result<Vec<domain::transaction::TransactionInfo>>;
+    async fn chain_parameters(&self) -> Result<domain::chain::ChainParameters>;
+    async fn get_dynamic_properties(
+        &self,
+    ) -> Result<domain::chain::DynamicProperties>;
+    /// Energy price changes over time
+    async fn get_energy_prices(&self) -> Result<domain::chain::PriceHistory>;
+    /// Bandwidth price changes over time
+    async fn get_bandwidth_prices(&self)
+    -> Result<domain::chain::PriceHistory>;
     async fn freeze_balance(
         &self,
         contract: domain::contract::FreezeBalanceV2Contract,
@@ -87,6 +99,83 @@ pub trait TronProvider {
         &self,
         address: TronAddress,
     ) -> Result<domain::account::DelegatedResourceAccountIndex>;
+    async fn get_contract_info(
+        &self,
+        contract_address: TronAddress,
+    ) -> Result<domain::contract::SmartContractDataWrapper>;
+    /// How many more unfreeze operations `owner` can start
+    async fn get_available_unfreeze_count(
+        &self,
+        owner: TronAddress,
+    ) -> Result<i64>;
+    /// TRX `owner` can withdraw at `timestamp` from expired unfreezes
+    async fn get_can_withdraw_unfreeze_amount(
+        &self,
+        owner: TronAddress,
+        timestamp: time::OffsetDateTime,
+    ) -> Result<Trx>;
+    /// Max TRX `owner` can delegate for `resource`
+    async fn get_can_delegated_max_size(
+        &self,
+        owner: TronAddress,
+        resource: domain::contract::ResourceCode,
+    ) -> Result<Trx>;
+    /// Witnesses with votes as of the last maintenance
+    async fn list_witnesses(&self) -> Result<Vec<domain::witness::Witness>>;
+    /// Witnesses with real-time votes of the current cycle, sorted by votes
+    async fn get_paginated_now_witness_list(
+        &self,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<domain::witness::Witness>>;
+    /// Percent of rewards kept by `witness`, the rest goes to voters
+    async fn get_brokerage_info(&self, witness: TronAddress) -> Result<i64>;
+    /// When votes are counted next
+    async fn get_next_maintenance_time(&self) -> Result<time::OffsetDateTime>;
+    /// TRC-10 asset by id, e.g. `"1002000"`
+    async fn get_asset_issue_by_id(
+        &self,
+        id: &str,
+    ) -> Result<domain::contract::AssetIssueContract>;
+    /// TRC-10 asset by name, fails if the name is not unique
+    async fn get_asset_issue_by_name(
+        &self,
+        name: &str,
+    ) -> Result<domain::contract::AssetIssueContract>;
+    async fn get_asset_issue_list(
+        &self,
+    ) -> Result<Vec<domain::contract::AssetIssueContract>>;
+    async fn list_exchanges(&self) -> Result<Vec<domain::exchange::Exchange>>;
+    async fn get_exchange_by_id(
+        &self,
+        exchange_id: i64,
+    ) -> Result<domain::exchange::Exchange>;
+    async fn get_market_order_by_account(
+        &self,
+        address: TronAddress,
+    ) -> Result<Vec<domain::market::MarketOrder>>;
+    async fn get_market_order_by_id(
+        &self,
+        order_id: Hash32,
+    ) -> Result<domain::market::MarketOrder>;
+    async fn get_market_pair_list(
+        &self,
+    ) -> Result<Vec<domain::market::MarketPair>>;
+    /// Price levels of the pair, best first
+    async fn get_market_price_by_pair(
+        &self,
+        pair: domain::market::MarketPair,
+    ) -> Result<Vec<domain::market::MarketPrice>>;
+    /// Active orders of the pair
+    async fn get_market_order_list_by_pair(
+        &self,
+        pair: domain::market::MarketPair,
+    ) -> Result<Vec<domain::market::MarketOrder>>;
+    async fn list_proposals(&self) -> Result<Vec<domain::proposal::Proposal>>;
+    async fn get_proposal_by_id(
+        &self,
+        proposal_id: i64,
+    ) -> Result<domain::proposal::Proposal>;
 
     // async fn calculate_fee(&self, transaction: &Transaction) -> Result<Fee>;
 
@@ -95,11 +184,6 @@ pub trait TronProvider {
     //     contract_address: TronAddress
     // ) -> Result<ContractAbi>;
 
-    // async fn get_contract_info(
-    //     &self,
-    //     contract_address: TronAddress
-    // ) -> Result<ContractInfo>;
-
     // async fn get_block_by_id(&self, block_id: [u8; 32]) -> Result<Block>;
 
     // async fn get_token_info(&self, token_id: String) -> Result<TokenInfo>;
@@ -0,0 +1,837 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tokio::time::{Duration, sleep};
+
+use crate::Result;
+use crate::client::Client;
+use crate::client::pending::AutoSigning;
+use crate::client::stake::StakePlanner;
+use crate::domain::Hash32;
+use crate::domain::account::{AccountResourceUsage, DelegatedResource};
+use crate::domain::address::TronAddress;
+use crate::domain::contract::ResourceCode;
+use crate::domain::trx::Trx;
+use crate::error::Error;
+use crate::provider::TronProvider;
+use crate::signer::PrehashSigner;
+
+/// Keep `receiver` above `min_available` of `resource`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DelegationTarget {
+    pub receiver: TronAddress,
+    pub resource: ResourceCode,
+    /// Top up when available resource drops below this value
+    pub min_available: i64,
+    /// TRX delegated per top up
+    pub top_up: Trx,
+    pub lock_period: Option<time::Duration>,
+}
+
+impl DelegationTarget {
+    /// Receiver with `available` resource must be topped up
+    pub fn needs_top_up(&self, available: i64) -> bool {
+        available < self.min_available
+    }
+}
+
+/// Delegation made by the manager
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ManagedDelegation {
+    pub receiver: TronAddress,
+    pub resource: ResourceCode,
+    pub amount: Trx,
+    /// Lock expiration in block time, can't be reclaimed before
+    #[serde(with = "time::serde::timestamp")]
+    pub locked_until: OffsetDateTime,
+}
+
+impl ManagedDelegation {
+    /// Lock expired by `now` for every on-chain delegation from the owner
+    /// to the receiver
+    pub fn unlocked(
+        &self,
+        on_chain: &[DelegatedResource],
+        now: OffsetDateTime,
+    ) -> bool {
+        on_chain.iter().all(|r| match self.resource {
+            ResourceCode::Energy => r.expire_time_for_energy <= now,
+            _ => r.expire_time_for_bandwidth <= now,
+        })
+    }
+}
+
+fn delegated(on_chain: &DelegatedResource, resource: ResourceCode) -> Trx {
+    match resource {
+        ResourceCode::Energy => on_chain.frozen_balance_for_energy,
+        _ => on_chain.frozen_balance_for_bandwidth,
+    }
+}
+
+/// Manager state, persisted with [`DelegationStore`] after every change
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DelegationPlan {
+    pub targets: Vec<DelegationTarget>,
+    pub delegations: Vec<ManagedDelegation>,
+}
+
+impl DelegationPlan {
+    pub fn target(
+        &self,
+        receiver: TronAddress,
+        resource: ResourceCode,
+    ) -> Option<&DelegationTarget> {
+        self.targets
+            .iter()
+            .find(|t| t.receiver == receiver && t.resource == resource)
+    }
+    /// Whether `delegation` may be undelegated: its lock expired and the
+    /// receiver, with `available` resource, stays at or above its target
+    /// without the `delegated` amount
+    pub fn reclaimable(
+        &self,
+        delegation: &ManagedDelegation,
+        available: i64,
+        delegated: i64,
+    ) -> bool {
+        self.target(delegation.receiver, delegation.resource)
+            .is_none_or(|target| !target.needs_top_up(available - delegated))
+    }
+    /// Trim delegations to the amount still delegated on chain to each
+    /// receiver, newest delegations are kept first. Returns whether the
+    /// plan changed.
+    pub fn reconcile(&mut self, on_chain: &[DelegatedResource]) -> bool {
+        let mut kept: Vec<ManagedDelegation> = Vec::new();
+        for delegation in self.delegations.iter().rev() {
+            let same = |receiver, resource| {
+                receiver == delegation.receiver
+                    && resource == delegation.resource
+            };
+            let total: Trx = on_chain
+                .iter()
+                .filter(|r| same(r.to, delegation.resource))
+                .map(|r| delegated(r, delegation.resource))
+                .sum();
+            let managed: Trx = kept
+                .iter()
+                .filter(|d| same(d.receiver, d.resource))
+                .map(|d| d.amount)
+                .sum();
+            let left = total - managed;
+            if left > Trx::ZERO {
+                kept.push(ManagedDelegation {
+                    amount: delegation.amount.min(left),
+                    ..delegation.clone()
+                });
+            }
+        }
+        kept.reverse();
+
+        let changed = kept != self.delegations;
+        self.delegations = kept;
+        changed
+    }
+}
+
+pub trait DelegationStore: Send + Sync + 'static {
+    type Error: Into<crate::error::Error> + Send + Sync + 'static;
+
+    fn store_plan(
+        &self,
+        plan: &DelegationPlan,
+    ) -> impl Future<Output = std::result::Result<(), Self::Error>> + Send;
+
+    fn load_plan(
+        &self,
+    ) -> impl Future<
+        Output = std::result::Result<Option<DelegationPlan>, Self::Error>,
+    > + Send;
+}
+
+#[derive(Clone, Default)]
+pub struct InMemoryDelegationStore {
+    plan: Arc<Mutex<Option<DelegationPlan>>>,
+}
+
+impl DelegationStore for InMemoryDelegationStore {
+    type Error = crate::error::Error;
+
+    fn store_plan(
+        &self,
+        plan: &DelegationPlan,
+    ) -> impl Future<Output = std::result::Result<(), Self::Error>> + Send {
+        let stored = self.plan.clone();
+        let plan = plan.clone();
+
+        async move {
+            *stored.lock().unwrap_or_else(|e| e.into_inner()) = Some(plan);
+            Ok(())
+        }
+    }
+
+    fn load_plan(
+        &self,
+    ) -> impl Future<
+        Output = std::result::Result<Option<DelegationPlan>, Self::Error>,
+    > + Send {
+        let stored = self.plan.clone();
+
+        async move { Ok(stored.lock().unwrap_or_else(|e| e.into_inner()).clone()) }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DelegationEvent {
+    ToppedUp {
+        receiver: TronAddress,
+        resource: ResourceCode,
+        amount: Trx,
+        txid: Hash32,
+    },
+    Reclaimed {
+        receiver: TronAddress,
+        resource: ResourceCode,
+        amount: Trx,
+        txid: Hash32,
+    },
+    Failed {
+        receiver: TronAddress,
+        error: Arc<Error>,
+    },
+}
+
+pub struct DelegationManagerHandle {
+    shutdown: Option<tokio::sync::oneshot::Sender<()>>,
+    rx: tokio::sync::broadcast::Receiver<DelegationEvent>,
+}
+
+impl DelegationManagerHandle {
+    pub fn events(&self) -> tokio::sync::broadcast::Receiver<DelegationEvent> {
+        self.rx.resubscribe()
+    }
+}
+
+impl Drop for DelegationManagerHandle {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+/// Delegates resources of `owner` to targets which run low, and reclaims
+/// delegations after their lock expires if the receiver stays above its
+/// threshold without them. Client signer must be able to sign for `owner`.
+pub struct DelegationManager<P, S: PrehashSigner, DS = InMemoryDelegationStore>
+{
+    client: Client<P, S>,
+    ctx: S::Ctx,
+    owner: TronAddress,
+    store: DS,
+    poll_interval: Duration,
+}
+
+impl<P, S> DelegationManager<P, S, InMemoryDelegationStore>
+where
+    S: PrehashSigner,
+{
+    pub fn new(
+        client: Client<P, S>,
+        ctx: S::Ctx,
+        owner: TronAddress,
+        poll_interval: Duration,
+    ) -> Self {
+        Self {
+            client,
+            ctx,
+            owner,
+            store: InMemoryDelegationStore::default(),
+            poll_interval,
+        }
+    }
+}
+
+fn available(resources: &AccountResourceUsage, resource: ResourceCode) -> i64 {
+    match resource {
+        ResourceCode::Energy => resources.energy_limit - resources.energy_used,
+        ResourceCode::Bandwidth => resources.net_limit - resources.net_used,
+        ResourceCode::TronPower => 0,
+    }
+}
+
+impl<P, S, DS> DelegationManager<P, S, DS>
+where
+    P: TronProvider + Clone + Send + Sync + 'static,
+    S: PrehashSigner + Clone + Send + Sync + 'static,
+    S::Ctx: Send + Sync + 'static,
+    Error: From<S::Error>,
+    DS: DelegationStore,
+{
+    pub fn with_store<DS2>(self, store: DS2) -> DelegationManager<P, S, DS2>
+    where
+        DS2: DelegationStore,
+    {
+        DelegationManager {
+            client: self.client,
+            ctx: self.ctx,
+            owner: self.owner,
+            store,
+            poll_interval: self.poll_interval,
+        }
+    }
+
+    /// Stored plan, or an empty one
+    pub async fn plan(&self) -> Result<DelegationPlan> {
+        Ok(self
+            .store
+            .load_plan()
+            .await
+            .map_err(Into::into)?
+            .unwrap_or_default())
+    }
+
+    /// Add or replace target for the same receiver and resource
+    pub async fn set_target(&self, target: DelegationTarget) -> Result<()> {
+        let mut plan = self.plan().await?;
+        plan.targets.retain(|t| {
+            t.receiver != target.receiver || t.resource != target.resource
+        });
+        plan.targets.push(target);
+        self.store.store_plan(&plan).await.map_err(Into::into)
+    }
+
+    /// Stop topping up the receiver, its delegations are reclaimed once
+    /// unlocked.
+    pub async fn remove_target(
+        &self,
+        receiver: TronAddress,
+        resource: ResourceCode,
+    ) -> Result<()> {
+        let mut plan = self.plan().await?;
+        plan.targets
+            .retain(|t| t.receiver != receiver || t.resource != resource);
+        self.store.store_plan(&plan).await.map_err(Into::into)
+    }
+
+    /// Delegations of `owner` on chain, including ones not made by
+    /// the manager
+    pub async fn active_delegations(&self) -> Result<Vec<DelegatedResource>> {
+        let provider = self.client.provider();
+        let index = provider.get_delegated_resource_account(self.owner).await?;
+        let mut delegations = Vec::new();
+        for receiver in index.to_accounts {
+            delegations.extend(
+                provider
+                    .get_delegated_resource(self.owner, receiver)
+                    .await?,
+            );
+        }
+        Ok(delegations)
+    }
+
+    /// Run one top up and reclaim round
+    pub async fn tick(&self) -> Vec<DelegationEvent> {
+        let mut events = Vec::new();
+        let mut plan = match self.plan().await {
+            Ok(plan) => plan,
+            Err(e) => {
+                events.push(DelegationEvent::Failed {
+                    receiver: self.owner,
+                    error: Arc::new(e),
+                });
+                return events;
+            }
+        };
+
+        let planner = match self
+            .client
+            .provider()
+            .get_account_resources(self.owner)
+            .await
+        {
+            Ok(resources) => StakePlanner::new(&resources),
+            Err(e) => {
+                events.push(DelegationEvent::Failed {
+                    receiver: self.owner,
+                    error: Arc::new(e),
+                });
+                return events;
+            }
+        };
+
+        // Locks are compared with block time, like the node does
+        let now = match self.client.provider().get_now_block().await {
+            Ok(block) => block.block_header.raw_data.timestamp,
+            Err(e) => {
+                events.push(DelegationEvent::Failed {
+                    receiver: self.owner,
+                    error: Arc::new(e),
+                });
+                return events;
+            }
+        };
+
+        // Forget delegations reclaimed outside of the manager
+        match self.active_delegations().await {
+            Ok(active) => {
+                if plan.reconcile(&active) {
+                    self.persist(&plan, &mut events).await;
+                }
+            }
+            Err(e) => events.push(DelegationEvent::Failed {
+                receiver: self.owner,
+                error: Arc::new(e),
+            }),
+        }
+
+        for target in plan.targets.clone() {
+            match self.top_up(&target, now).await {
+                Ok(Some((delegation, txid))) => {
+                    events.push(DelegationEvent::ToppedUp {
+                        receiver: delegation.receiver,
+                        resource: delegation.resource,
+                        amount: delegation.amount,
+                        txid,
+                    });
+                    plan.delegations.push(delegation);
+                    self.persist(&plan, &mut events).await;
+                }
+                Ok(None) => (),
+                Err(e) => events.push(DelegationEvent::Failed {
+                    receiver: target.receiver,
+                    error: Arc::new(e),
+                }),
+            }
+        }
+
+        let mut index = 0;
+        while index < plan.delegations.len() {
+            let delegation = plan.delegations[index].clone();
+            if delegation.locked_until > now {
+                index += 1;
+                continue;
+            }
+            match self.reclaim(&plan, &delegation, &planner, now).await {
+                Ok(Some(txid)) => {
+                    events.push(DelegationEvent::Reclaimed {
+                        receiver: delegation.receiver,
+                        resource: delegation.resource,
+                        amount: delegation.amount,
+                        txid,
+                    });
+                    plan.delegations.remove(index);
+                    self.persist(&plan, &mut events).await;
+                }
+                Ok(None) => index += 1,
+                Err(e) => {
+                    events.push(DelegationEvent::Failed {
+                        receiver: delegation.receiver,
+                        error: Arc::new(e),
+                    });
+                    index += 1;
+                }
+            }
+        }
+
+        events
+    }
+
+    pub fn run(self) -> DelegationManagerHandle {
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+        let (tx, rx) = tokio::sync::broadcast::channel(128);
+
+        tokio::spawn(async move {
+            loop {
+                for event in self.tick().await {
+                    if let DelegationEvent::Failed { receiver, error } = &event
+                    {
+                        tracing::warn!(%receiver, ?error, "delegation failed");
+                    }
+                    let _ = tx.send(event);
+                }
+
+                tokio::select! {
+                    _ = sleep(self.poll_interval) => {}
+                    _ = &mut shutdown_rx => {
+                        tracing::info!("exiting from delegation manager");
+                        break;
+                    }
+                }
+            }
+        });
+
+        DelegationManagerHandle {
+            shutdown: Some(shutdown_tx),
+            rx,
+        }
+    }
+
+    async fn persist(
+        &self,
+        plan: &DelegationPlan,
+        events: &mut Vec<DelegationEvent>,
+    ) {
+        if let Err(e) = self.store.store_plan(plan).await {
+            events.push(DelegationEvent::Failed {
+                receiver: self.owner,
+                error: Arc::new(e.into()),
+            });
+        }
+    }
+
+    async fn top_up(
+        &self,
+        target: &DelegationTarget,
+        now: OffsetDateTime,
+    ) -> Result<Option<(ManagedDelegation, Hash32)>> {
+        let resources = self
+            .client
+            .provider()
+            .get_account_resources(target.receiver)
+            .await?;
+        if !target.needs_top_up(available(&resources, target.resource)) {
+            return Ok(None);
+        }
+
+        let txid = self
+            .client
+            .delegate()
+            .owner(self.owner)
+            .receiver(target.receiver)
+            .resource(target.resource)
+            .amount(target.top_up)
+            .maybe_lock_period(target.lock_period)
+            .build::<AutoSigning>()
+            .await?
+            .broadcast(&self.ctx)
+            .await?;
+        tracing::info!(receiver = %target.receiver, amount = %target.top_up, "delegated");
+
+        Ok(Some((
+            ManagedDelegation {
+                receiver: target.receiver,
+                resource: target.resource,
+                amount: target.top_up,
+                locked_until: now + target.lock_period.unwrap_or_default(),
+            },
+            txid,
+        )))
+    }
+
+    /// Undelegate if the lock expired on chain and receiver won't drop
+    /// below its target threshold
+    async fn reclaim(
+        &self,
+        plan: &DelegationPlan,
+        delegation: &ManagedDelegation,
+        planner: &StakePlanner,
+        now: OffsetDateTime,
+    ) -> Result<Option<Hash32>> {
+        let provider = self.client.provider();
+
+        let on_chain = provider
+            .get_delegated_resource(self.owner, delegation.receiver)
+            .await?;
+        if !delegation.unlocked(&on_chain, now) {
+            return Ok(None);
+        }
+
+        if plan
+            .target(delegation.receiver, delegation.resource)
+            .is_some()
+        {
+            let resources =
+                provider.get_account_resources(delegation.receiver).await?;
+            let delegated =
+                planner.daily_yield(delegation.amount, delegation.resource);
+            if !plan.reclaimable(
+                delegation,
+                available(&resources, delegation.resource),
+                delegated,
+            ) {
+                return Ok(None);
+            }
+        }
+
+        let txid = self
+            .client
+            .undelegate()
+            .owner(self.owner)
+            .receiver(delegation.receiver)
+            .resource(delegation.resource)
+            .amount(delegation.amount)
+            .build::<AutoSigning>()
+            .await?
+            .broadcast(&self.ctx)
+            .await?;
+        tracing::info!(receiver = %delegation.receiver, amount = %delegation.amount, "undelegated");
+
+        Ok(Some(txid))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use time::ext::NumericalDuration;
+
+    use super::*;
+    use crate::domain::account::Account;
+    use crate::domain::block::{BlockExtention, BlockHeader, RawBlockHeader};
+    use crate::domain::chain::ChainParameters;
+    use crate::domain::contract::ContractType;
+    use crate::domain::proposal::ChainParameter;
+    use crate::protocol;
+    use crate::provider::mock::MockProvider;
+    use crate::signer::LocalSigner;
+
+    fn receiver() -> TronAddress {
+        let mut bytes = [7; 21];
+        bytes[0] = 0x41;
+        TronAddress::try_from(bytes.as_slice()).unwrap_or_default()
+    }
+
+    #[test]
+    fn top_up_and_reclaim() {
+        let now = OffsetDateTime::UNIX_EPOCH + 100.days();
+        let target = DelegationTarget {
+            receiver: receiver(),
+            resource: ResourceCode::Energy,
+            min_available: 100_000,
+            top_up: Trx::from_sun(1_000_000_000),
+            lock_period: Some(3.days()),
+        };
+        assert!(target.needs_top_up(99_999));
+        assert!(!target.needs_top_up(100_000));
+
+        let delegation = ManagedDelegation {
+            receiver: receiver(),
+            resource: ResourceCode::Energy,
+            amount: target.top_up,
+            locked_until: now - 1.hours(),
+        };
+        let on_chain = |expire| DelegatedResource {
+            to: receiver(),
+            frozen_balance_for_energy: delegation.amount,
+            expire_time_for_energy: expire,
+            ..Default::default()
+        };
+        assert!(delegation.unlocked(&[on_chain(now - 1.hours())], now));
+        assert!(!delegation.unlocked(&[on_chain(now + 1.hours())], now));
+
+        let mut plan = DelegationPlan {
+            targets: vec![target],
+            delegations: vec![delegation.clone()],
+        };
+        // Receiver would drop below its threshold without the delegation
+        assert!(!plan.reclaimable(&delegation, 150_000, 60_000));
+        assert!(plan.reclaimable(&delegation, 200_000, 60_000));
+        // Target removed, reclaim regardless
+        plan.targets.clear();
+        assert!(plan.reclaimable(&delegation, 0, 60_000));
+    }
+
+    #[test]
+    fn plan_round_trips() {
+        let plan = DelegationPlan {
+            targets: vec![DelegationTarget {
+                receiver: receiver(),
+                resource: ResourceCode::Bandwidth,
+                min_available: 5_000,
+                top_up: Trx::from_sun(100_000_000),
+                lock_period: None,
+            }],
+            delegations: vec![ManagedDelegation {
+                receiver: receiver(),
+                resource: ResourceCode::Bandwidth,
+                amount: Trx::from_sun(100_000_000),
+                locked_until: OffsetDateTime::UNIX_EPOCH + 1.days(),
+            }],
+        };
+        let json = serde_json::to_string(&plan).unwrap_or_default();
+        assert_eq!(
+            serde_json::from_str::<DelegationPlan>(&json).ok(),
+            Some(plan)
+        );
+    }
+
+    #[test]
+    fn plan_is_trimmed_to_on_chain_amount() {
+        let managed = |amount, days: i64| ManagedDelegation {
+            receiver: receiver(),
+            resource: ResourceCode::Energy,
+            amount: Trx::from_sun(amount),
+            locked_until: OffsetDateTime::UNIX_EPOCH + days.days(),
+        };
+        let on_chain = |energy, bandwidth| DelegatedResource {
+            to: receiver(),
+            frozen_balance_for_energy: Trx::from_sun(energy),
+            frozen_balance_for_bandwidth: Trx::from_sun(bandwidth),
+            ..Default::default()
+        };
+        let mut plan = DelegationPlan {
+            targets: Vec::new(),
+            delegations: vec![managed(300, 1), managed(500, 2)],
+        };
+
+        assert!(!plan.reconcile(&[on_chain(600, 0), on_chain(200, 900)]));
+        assert_eq!(plan.delegations, vec![managed(300, 1), managed(500, 2)]);
+
+        // Newest delegation is kept first
+        assert!(plan.reconcile(&[on_chain(600, 900)]));
+        assert_eq!(plan.delegations, vec![managed(100, 1), managed(500, 2)]);
+
+        assert!(plan.reconcile(&[on_chain(0, 900)]));
+        assert!(plan.delegations.is_empty());
+    }
+
+    fn set_block_time(provider: &MockProvider, timestamp: OffsetDateTime) {
+        provider.update(|s| {
+            let number = s.blocks.len() as i64 + 1;
+            let block = BlockExtention {
+                transactions: Vec::new(),
+                block_header: BlockHeader {
+                    raw_data: RawBlockHeader {
+                        timestamp,
+                        tx_trie_root: Default::default(),
+                        parent_hash: Default::default(),
+                        number,
+                        witness_id: 0,
+                        witness_address: Default::default(),
+                        version: 0,
+                        account_state_root: Default::default(),
+                    },
+                    witness_signature: None,
+                    pq_auth_sig: None,
+                },
+                blockid: Default::default(),
+            };
+            s.blocks.insert(number, block);
+        });
+    }
+
+    fn set_energy(provider: &MockProvider, address: TronAddress, limit: i64) {
+        provider.update(|s| {
+            s.account_resources.insert(
+                address,
+                AccountResourceUsage::from(protocol::AccountResourceMessage {
+                    free_net_limit: 5_000,
+                    energy_limit: limit,
+                    // 5 energy per staked TRX
+                    total_energy_limit: 90_000_000_000,
+                    total_energy_weight: 18_000_000_000,
+                    ..Default::default()
+                }),
+            );
+        });
+    }
+
+    fn chain_parameters() -> Option<ChainParameters> {
+        let raw = [
+            ChainParameter::CreateAccountFee,
+            ChainParameter::TransactionFee,
+            ChainParameter::CreateNewAccountFeeInSystemContract,
+            ChainParameter::EnergyFee,
+        ]
+        .iter()
+        .filter_map(|p| Some((p.key()?.to_string(), 100)))
+        .collect::<HashMap<_, _>>();
+        ChainParameters::from_raw(raw).ok()
+    }
+
+    #[tokio::test]
+    async fn tick_tops_up_and_reclaims() {
+        let signer = LocalSigner::rand();
+        let owner = signer.address();
+        let provider = MockProvider::new().await;
+        let start = OffsetDateTime::UNIX_EPOCH + 100.days();
+        set_block_time(&provider, start);
+        set_energy(&provider, owner, 0);
+        set_energy(&provider, receiver(), 0);
+        provider.update(|s| {
+            s.chain_parameters = chain_parameters();
+            s.accounts.insert(
+                owner,
+                Account {
+                    balance: Trx::from_sun(10_000_000_000),
+                    ..Default::default()
+                },
+            );
+        });
+
+        let client = Client::builder()
+            .provider(provider.clone())
+            .signer(signer)
+            .build();
+        let manager =
+            DelegationManager::new(client, (), owner, Duration::from_secs(3));
+        let top_up = Trx::from_sun(1_000_000_000);
+        let set_target = manager.set_target(DelegationTarget {
+            receiver: receiver(),
+            resource: ResourceCode::Energy,
+            min_available: 100_000,
+            top_up,
+            lock_period: Some(3.days()),
+        });
+        assert!(set_target.await.is_ok());
+
+        let events = manager.tick().await;
+        assert!(matches!(
+            events.as_slice(),
+            [DelegationEvent::ToppedUp { amount, .. }] if *amount == top_up
+        ));
+        let broadcasts = provider.state(|s| s.broadcasts.clone());
+        assert!(matches!(
+            broadcasts.as_slice(),
+            [tx] if matches!(
+                tx.raw.contract[0].contract_type,
+                ContractType::DelegateResourceContract(_)
+            )
+        ));
+        let plan = manager.plan().await.unwrap_or_default();
+        assert_eq!(
+            plan.delegations
+                .iter()
+                .map(|d| d.locked_until)
+                .collect::<Vec<_>>(),
+            vec![start + 3.days()]
+        );
+
+        // Delegation landed and was partially reclaimed by someone else
+        set_energy(&provider, receiver(), 200_000);
+        provider.update(|s| {
+            s.delegated_resources.push(DelegatedResource {
+                from: owner,
+                to: receiver(),
+                frozen_balance_for_energy: Trx::from_sun(400_000_000),
+                expire_time_for_energy: start + 3.days(),
+                ..Default::default()
+            })
+        });
+        set_block_time(&provider, start + 1.days());
+        assert!(manager.tick().await.is_empty());
+        let plan = manager.plan().await.unwrap_or_default();
+        assert_eq!(
+            plan.delegations
+                .iter()
+                .map(|d| d.amount)
+                .collect::<Vec<_>>(),
+            vec![Trx::from_sun(400_000_000)]
+        );
+
+        // Lock expired by block time
+        set_block_time(&provider, start + 4.days());
+        let events = manager.tick().await;
+        assert!(matches!(
+            events.as_slice(),
+            [DelegationEvent::Reclaimed { amount, .. }]
+                if *amount == Trx::from_sun(400_000_000)
+        ));
+        assert_eq!(provider.state(|s| s.broadcasts.len()), 2);
+        let plan = manager.plan().await.unwrap_or_default();
+        assert!(plan.delegations.is_empty());
+    }
+}
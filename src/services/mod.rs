@@ -0,0 +1,4 @@
+//! Background services built on top of [`Client`](crate::client::Client)
+
+pub mod delegation_manager;
+pub mod unfreeze_keeper;
@@ -0,0 +1,265 @@
+use std::sync::Arc;
+
+use time::OffsetDateTime;
+use tokio::time::{Duration, sleep};
+
+use crate::Result;
+use crate::client::Client;
+use crate::client::pending::AutoSigning;
+use crate::domain::Hash32;
+use crate::domain::account::UnFreezeV2;
+use crate::domain::address::TronAddress;
+use crate::domain::contract::ResourceCode;
+use crate::domain::trx::Trx;
+use crate::error::Error;
+use crate::provider::TronProvider;
+use crate::signer::PrehashSigner;
+
+/// Shortest wait between checks, even if an unfreeze matures sooner
+const MIN_WAIT: Duration = Duration::from_secs(3);
+
+/// Pending unfreezes of a watched owner
+#[derive(Clone, Debug)]
+pub struct UnfreezeStatus {
+    pub owner: TronAddress,
+    pub pending: Vec<UnFreezeV2>,
+    /// TRX which can be withdrawn now
+    pub withdrawable: Trx,
+    /// Unfreeze operations owner can still start, withdrawing matured
+    /// unfreezes frees slots
+    pub available_unfreeze_count: i64,
+    /// When the next pending unfreeze matures
+    pub next_maturity: Option<OffsetDateTime>,
+}
+
+#[derive(Clone, Debug)]
+pub enum UnfreezeEvent {
+    Status(UnfreezeStatus),
+    Withdrawn {
+        owner: TronAddress,
+        amount: Trx,
+        txid: Hash32,
+    },
+    Failed {
+        owner: TronAddress,
+        error: Arc<Error>,
+    },
+}
+
+pub struct UnfreezeKeeperHandle {
+    shutdown: Option<tokio::sync::oneshot::Sender<()>>,
+    rx: tokio::sync::broadcast::Receiver<UnfreezeEvent>,
+}
+
+impl UnfreezeKeeperHandle {
+    pub fn events(&self) -> tokio::sync::broadcast::Receiver<UnfreezeEvent> {
+        self.rx.resubscribe()
+    }
+}
+
+impl Drop for UnfreezeKeeperHandle {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+/// Watches owners' pending unfreezes and withdraws them as soon as they
+/// mature. Client signer must be able to sign for every owner.
+pub struct UnfreezeKeeper<P, S: PrehashSigner> {
+    client: Client<P, S>,
+    ctx: S::Ctx,
+    owners: Vec<TronAddress>,
+    poll_interval: Duration,
+}
+
+impl<P, S> UnfreezeKeeper<P, S>
+where
+    P: TronProvider + Clone + Send + Sync + 'static,
+    S: PrehashSigner + Clone + Send + Sync + 'static,
+    S::Ctx: Send + Sync + 'static,
+    Error: From<S::Error>,
+{
+    /// `poll_interval` is the max time between checks, keeper also wakes up
+    /// when the nearest unfreeze matures.
+    pub fn new(
+        client: Client<P, S>,
+        ctx: S::Ctx,
+        owners: impl IntoIterator<Item = TronAddress>,
+        poll_interval: Duration,
+    ) -> Self {
+        Self {
+            client,
+            ctx,
+            owners: owners.into_iter().collect(),
+            poll_interval,
+        }
+    }
+
+    pub fn run(self) -> UnfreezeKeeperHandle {
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+        let (tx, rx) = tokio::sync::broadcast::channel(128);
+
+        tokio::spawn(async move {
+            loop {
+                let mut next_maturity: Option<time::Duration> = None;
+                for owner in &self.owners {
+                    match self.check(*owner, &tx).await {
+                        Ok(Some(left)) => {
+                            next_maturity = Some(
+                                next_maturity.map_or(left, |m| m.min(left)),
+                            );
+                        }
+                        Ok(None) => (),
+                        Err(e) => {
+                            tracing::warn!(%owner, ?e, "unfreeze check failed");
+                            let _ = tx.send(UnfreezeEvent::Failed {
+                                owner: *owner,
+                                error: Arc::new(e),
+                            });
+                        }
+                    }
+                }
+
+                let wait = wait_for(next_maturity, self.poll_interval);
+
+                tokio::select! {
+                    _ = sleep(wait) => {}
+                    _ = &mut shutdown_rx => {
+                        tracing::info!("exiting from unfreeze keeper");
+                        break;
+                    }
+                }
+            }
+        });
+
+        UnfreezeKeeperHandle {
+            shutdown: Some(shutdown_tx),
+            rx,
+        }
+    }
+
+    /// Withdraw matured unfreezes of `owner`, returns chain time left until
+    /// the next maturity
+    async fn check(
+        &self,
+        owner: TronAddress,
+        tx: &tokio::sync::broadcast::Sender<UnfreezeEvent>,
+    ) -> Result<Option<time::Duration>> {
+        let provider = self.client.provider();
+        // Chain time decides maturity, not the local clock
+        let now = provider
+            .get_now_block()
+            .await?
+            .block_header
+            .raw_data
+            .timestamp;
+        let (account, available_unfreeze_count, withdrawable) = tokio::try_join!(
+            provider.get_account(owner),
+            provider.get_available_unfreeze_count(owner),
+            provider.get_can_withdraw_unfreeze_amount(owner, now)
+        )?;
+
+        let pending = account.unfrozen_v2;
+        let (next_maturity, matured) = maturity(&pending, now);
+
+        let _ = tx.send(UnfreezeEvent::Status(UnfreezeStatus {
+            owner,
+            pending,
+            withdrawable,
+            available_unfreeze_count,
+            next_maturity,
+        }));
+
+        if let Some(resource) = matured
+            && withdrawable > Trx::ZERO
+        {
+            let txid = self
+                .client
+                .withdraw_unfreeze()
+                .owner(owner)
+                .resource(resource)
+                .build::<AutoSigning>()
+                .await?
+                .broadcast(&self.ctx)
+                .await?;
+            tracing::info!(%owner, %withdrawable, "withdrawn unfrozen trx");
+            let _ = tx.send(UnfreezeEvent::Withdrawn {
+                owner,
+                amount: withdrawable,
+                txid,
+            });
+        }
+
+        Ok(next_maturity.map(|at| at - now))
+    }
+}
+
+/// Next maturity after chain time `now` and the resource of a matured
+/// unfreeze, if any
+fn maturity(
+    pending: &[UnFreezeV2],
+    now: OffsetDateTime,
+) -> (Option<OffsetDateTime>, Option<ResourceCode>) {
+    let next_maturity = pending
+        .iter()
+        .map(|u| u.unfreeze_expire_time)
+        .filter(|expire| *expire > now)
+        .min();
+    let matured = pending
+        .iter()
+        .find(|u| u.unfreeze_expire_time <= now)
+        .map(|u| u.unfreeze_type);
+    (next_maturity, matured)
+}
+
+/// Sleep before the next check, `left` is chain time until the nearest
+/// maturity
+fn wait_for(left: Option<time::Duration>, poll_interval: Duration) -> Duration {
+    left.map(|left| {
+        // One block later, so the node sees it as matured
+        Duration::try_from(left + time::Duration::seconds(3))
+            .unwrap_or_default()
+            .clamp(MIN_WAIT, poll_interval.max(MIN_WAIT))
+    })
+    .unwrap_or(poll_interval)
+}
+
+#[cfg(test)]
+mod test {
+    use time::ext::NumericalDuration;
+
+    use super::*;
+
+    #[test]
+    fn selects_matured_and_next_unfreeze() {
+        let now = OffsetDateTime::UNIX_EPOCH + 10.days();
+        let unfreeze = |unfreeze_type, at| UnFreezeV2 {
+            unfreeze_type,
+            unfreeze_amount: Trx::from_sun(1_000_000),
+            unfreeze_expire_time: at,
+        };
+        let pending = [
+            unfreeze(ResourceCode::Energy, now + 2.days()),
+            unfreeze(ResourceCode::Bandwidth, now - 1.seconds()),
+            unfreeze(ResourceCode::Energy, now + 1.days()),
+        ];
+        assert_eq!(
+            maturity(&pending, now),
+            (Some(now + 1.days()), Some(ResourceCode::Bandwidth))
+        );
+        assert_eq!(maturity(&pending[..1], now), (Some(now + 2.days()), None));
+        assert_eq!(maturity(&[], now), (None, None));
+    }
+
+    #[test]
+    fn wait_is_bounded() {
+        let poll = Duration::from_secs(600);
+        assert_eq!(wait_for(None, poll), poll);
+        assert_eq!(wait_for(Some(1.days()), poll), poll);
+        assert_eq!(wait_for(Some(60.seconds()), poll), Duration::from_secs(63));
+        // Already matured on chain, don't spin
+        assert_eq!(wait_for(Some((-60).seconds()), poll), MIN_WAIT);
+    }
+}
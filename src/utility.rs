@@ -1,21 +1,26 @@
+use prost::encoding::encoded_len_varint;
 use sha2::{Digest, Sha256};
 
 use crate::domain::Hash32;
 
-pub fn estimate_bandwidth(
-    raw_data_len_bytes: i64,
-    signature_list_size: i64,
+/// Result size reserved by the node for every contract in a transaction
+const MAX_RESULT_SIZE: i64 = 64;
+/// Length of a recoverable secp256k1 signature (r, s, v)
+const SIGNATURE_LEN: usize = 65;
+
+/// Exact bandwidth consumed by a transaction: encoded size of `Transaction`
+/// (raw data and signatures, without `ret`) plus reserved result size for
+/// each contract, as the node calculates it.
+pub fn transaction_bandwidth(
+    raw_data_len_bytes: usize,
+    signature_count: usize,
+    contract_count: usize,
 ) -> i64 {
-    const DATA_PROTOBUF_EXTRA: i64 = 3;
-    const MAX_RESULT_SIZE: i64 = 64;
-    const SIGNATURE_SIZE: i64 = 67;
-
-    let mut estimated_bandwidth =
-        raw_data_len_bytes + DATA_PROTOBUF_EXTRA + MAX_RESULT_SIZE;
-    for _ in 0..signature_list_size {
-        estimated_bandwidth += SIGNATURE_SIZE;
-    }
-    estimated_bandwidth
+    // Length-delimited field: tag (1 byte) + varint length + payload
+    let field_len = |len: usize| 1 + encoded_len_varint(len as u64) + len;
+    let encoded = field_len(raw_data_len_bytes)
+        + signature_count * field_len(SIGNATURE_LEN);
+    encoded as i64 + contract_count as i64 * MAX_RESULT_SIZE
 }
 
 pub trait TronOffsetDateTime: Sized {
@@ -52,3 +57,33 @@ pub fn generate_txid(raw_data: &[u8]) -> Hash32 {
     let hash: [u8; 32] = digest.into();
     hash.into()
 }
+
+#[cfg(test)]
+mod test {
+    use prost::Message;
+
+    use crate::protocol;
+
+    use super::*;
+
+    #[test]
+    fn transaction_bandwidth_matches_encoded_size() {
+        for (data_len, signatures) in [(0, 1), (10, 1), (300, 2), (5000, 5)] {
+            let raw = protocol::transaction::Raw {
+                data: vec![1; data_len],
+                contract: vec![Default::default()],
+                ..Default::default()
+            };
+            let raw_len = raw.encoded_len();
+            let tx = protocol::Transaction {
+                raw_data: Some(raw),
+                signature: vec![vec![0xff; SIGNATURE_LEN]; signatures],
+                ..Default::default()
+            };
+            assert_eq!(
+                transaction_bandwidth(raw_len, signatures, 1),
+                tx.encoded_len() as i64 + MAX_RESULT_SIZE
+            );
+        }
+    }
+}